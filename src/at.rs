@@ -1,14 +1,129 @@
-use chrono::Timelike;
-use chrono::{Duration, NaiveTime};
+use chrono::{DateTime, Duration, NaiveTime};
 
 use crate::interval::{Interval, Timeline};
-use crate::utils::{end_of, start_of, Of};
+use crate::types::Dimension;
+use crate::zone::Zone;
 
+/// A set of times repeated every day
 #[derive(Debug, PartialEq)]
 pub struct At(Vec<NaiveTime>);
 
 impl At {
-	pub fn new(times: Vec<NaiveTime>) -> Self {
+	pub fn new(mut times: Vec<NaiveTime>) -> Self {
+		times.sort();
+		times.dedup();
 		At(times)
 	}
-}
\ No newline at end of file
+}
+
+impl Timeline for At {
+	fn duration_hint(&self) -> Duration {
+		Duration::days(1)
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		Box::new(AtIterator::new(self.0.clone(), interval))
+	}
+
+	fn cycle(&self) -> Option<Dimension> {
+		Some(Dimension::Day)
+	}
+}
+
+pub struct AtIterator {
+	times: Vec<NaiveTime>,
+	interval: Interval,
+	completed: bool,
+}
+
+impl AtIterator {
+	pub fn new(times: Vec<NaiveTime>, interval: Interval) -> Self {
+		AtIterator {
+			completed: times.is_empty(),
+			times,
+			interval,
+		}
+	}
+}
+
+impl Iterator for AtIterator {
+	type Item = Interval;
+
+	fn next(&mut self) -> Option<Interval> {
+		if self.completed {
+			return None;
+		}
+
+		let zone = self.interval.from.timezone();
+		let mut day = self.interval.from.date_naive();
+
+		loop {
+			// A time can resolve to zero, one or two instants around DST
+			// transitions, so they are sorted again after resolving
+			let mut candidates: Vec<DateTime<Zone>> = self
+				.times
+				.iter()
+				.flat_map(|time| zone.occurrences(&day.and_time(*time)))
+				.filter(|datetime| *datetime >= self.interval.from)
+				.collect();
+			candidates.sort();
+
+			let datetime = match candidates.first() {
+				Some(datetime) => *datetime,
+				None => {
+					day = day.succ_opt().unwrap();
+					let start = zone.localize(&day.and_time(NaiveTime::MIN));
+					if self.interval.to.is_some()
+						&& start >= *self.interval.to.as_ref().unwrap()
+					{
+						self.completed = true;
+						return None;
+					}
+					continue;
+				}
+			};
+
+			if self.interval.to.is_some()
+				&& datetime >= *self.interval.to.as_ref().unwrap()
+			{
+				self.completed = true;
+				return None;
+			}
+
+			self.interval.from = datetime + Duration::nanoseconds(1);
+
+			return Some(Interval {
+				from: datetime,
+				to: Some(datetime),
+			});
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use insta::assert_debug_snapshot_matches;
+
+	#[test]
+	fn at_7_pm_20_30_and_8_am() {
+		let at = At::new(vec![
+			NaiveTime::from_hms_opt(19, 0, 0).unwrap(),
+			NaiveTime::from_hms_opt(20, 30, 0).unwrap(),
+			NaiveTime::from_hms_opt(20, 30, 0).unwrap(),
+			NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+		]);
+
+		assert_debug_snapshot_matches!(
+			"at_7_pm_20_30_and_8_am",
+			at.iter_within(
+				Interval::from("2019-10-31T19:30:00Z".parse().unwrap())
+					.until("2019-11-02T19:00:00Z".parse().unwrap())
+			)
+			.collect::<Vec<_>>()
+		);
+	}
+}
@@ -0,0 +1,503 @@
+use chrono::{Datelike, Duration, Month, NaiveDate, Weekday};
+
+use crate::date::whole_day;
+use crate::interval::{Interval, Timeline};
+use crate::utils::days_in_month;
+use crate::weekday::Weekend;
+
+/// How the date of a holiday is computed every year
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HolidayRule {
+	/// The same day of the same month, like christmas
+	Fixed(Month, u32),
+	/// The nth weekday of a month, like the 4th thursday of november.
+	/// Negative values count from the end of the month.
+	NthWeekday(i32, Weekday, Month),
+	/// A number of days from easter sunday, like -2 for good friday
+	Easter(i64),
+}
+
+impl HolidayRule {
+	fn date(&self, year: i32) -> Option<NaiveDate> {
+		match *self {
+			HolidayRule::Fixed(month, day) => {
+				NaiveDate::from_ymd_opt(year, month.number_from_month(), day)
+			}
+			HolidayRule::NthWeekday(nth, weekday, month) => {
+				let month = month.number_from_month();
+				let weekday = weekday.num_days_from_monday() as i64;
+				let date = if nth > 0 {
+					let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+					let ahead = (7 + weekday
+						- first.weekday().num_days_from_monday() as i64)
+						% 7;
+					first + Duration::days(ahead + 7 * i64::from(nth - 1))
+				} else {
+					let last = NaiveDate::from_ymd_opt(
+						year,
+						month,
+						days_in_month(year, month),
+					)?;
+					let behind = (7 + last.weekday().num_days_from_monday()
+						as i64 - weekday) % 7;
+					last - Duration::days(behind + 7 * i64::from(-nth - 1))
+				};
+
+				Some(date).filter(|date| date.month() == month)
+			}
+			HolidayRule::Easter(days) => {
+				easter(year).map(|easter| easter + Duration::days(days))
+			}
+		}
+	}
+}
+
+/// Easter sunday in the gregorian calendar, using the anonymous computus
+pub fn easter(year: i32) -> Option<NaiveDate> {
+	let a = year % 19;
+	let b = year / 100;
+	let c = year % 100;
+	let d = b / 4;
+	let e = b % 4;
+	let f = (b + 8) / 25;
+	let g = (b - f + 1) / 3;
+	let h = (19 * a + b - d - g + 15) % 30;
+	let i = c / 4;
+	let k = c % 4;
+	let l = (32 + 2 * e + 2 * i - h - k) % 7;
+	let m = (a + 11 * h + 22 * l) / 451;
+	let month = (h + l - 7 * m + 114) / 31;
+	let day = (h + l - 7 * m + 114) % 31 + 1;
+
+	NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// Which day off is given when a holiday falls on a weekend
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Observance {
+	/// The holiday is observed on its date
+	Actual,
+	/// The closest weekday, the one after when both are as close. With
+	/// the usual weekend saturdays move to the friday before and sundays
+	/// to the monday after, like federal holidays in the US
+	NearestWeekday,
+	/// Weekends move to the next weekday that isn't already a holiday,
+	/// like substitute days in the UK
+	NextFreeWeekday,
+}
+
+/// A named holiday in a [`Calendar`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holiday {
+	name: String,
+	rule: HolidayRule,
+	observance: Observance,
+	first_year: Option<i32>,
+}
+
+impl Holiday {
+	pub fn new(name: impl Into<String>, rule: HolidayRule) -> Self {
+		Holiday {
+			name: name.into(),
+			rule,
+			observance: Observance::Actual,
+			first_year: None,
+		}
+	}
+
+	/// Observe the holiday on another day when it falls on a weekend
+	pub fn with_observance(mut self, observance: Observance) -> Self {
+		self.observance = observance;
+		self
+	}
+
+	/// Only observe the holiday from the given year on
+	pub fn with_first_year(mut self, year: i32) -> Self {
+		self.first_year = Some(year);
+		self
+	}
+
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+}
+
+/// Where an occurrence moves when it doesn't fall on a business day
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Adjustment {
+	/// The next business day
+	Following,
+	/// The previous business day
+	Preceding,
+	/// The next business day, or the previous one when the next is in
+	/// another month
+	ModifiedFollowing,
+	/// The closest business day, the next one when both are as close
+	Nearest,
+}
+
+/// A set of holidays computed every year, producing the days they are
+/// observed on, and the weekend they are observed around
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calendar {
+	holidays: Vec<Holiday>,
+	weekend: Weekend,
+}
+
+impl Calendar {
+	pub fn new(holidays: Vec<Holiday>) -> Self {
+		Calendar {
+			holidays,
+			weekend: Weekend::default(),
+		}
+	}
+
+	/// Use another weekend than saturday and sunday.
+	///
+	/// # Panics
+	///
+	/// Panics if every day of the week belongs to the weekend, as there
+	/// would be no business days.
+	pub fn with_weekend(mut self, weekend: Weekend) -> Self {
+		assert!(weekend.days_per_week() < 7, "no business days left");
+		self.weekend = weekend;
+		self
+	}
+
+	/// Federal holidays in the United States
+	pub fn us_federal() -> Self {
+		use HolidayRule::*;
+		use Month::*;
+		use Weekday::*;
+
+		let nearest = |name, rule| {
+			Holiday::new(name, rule).with_observance(Observance::NearestWeekday)
+		};
+
+		Calendar::new(vec![
+			nearest("New Year's Day", Fixed(January, 1)),
+			Holiday::new(
+				"Martin Luther King Jr. Day",
+				NthWeekday(3, Mon, January),
+			),
+			Holiday::new("Washington's Birthday", NthWeekday(3, Mon, February)),
+			Holiday::new("Memorial Day", NthWeekday(-1, Mon, May)),
+			nearest("Juneteenth", Fixed(June, 19)).with_first_year(2021),
+			nearest("Independence Day", Fixed(July, 4)),
+			Holiday::new("Labor Day", NthWeekday(1, Mon, September)),
+			Holiday::new("Columbus Day", NthWeekday(2, Mon, October)),
+			nearest("Veterans Day", Fixed(November, 11)),
+			Holiday::new("Thanksgiving Day", NthWeekday(4, Thu, November)),
+			nearest("Christmas Day", Fixed(December, 25)),
+		])
+	}
+
+	/// Bank holidays in England and Wales
+	pub fn england_and_wales() -> Self {
+		use HolidayRule::*;
+		use Month::*;
+		use Weekday::*;
+
+		let substitute = |name, rule| {
+			Holiday::new(name, rule)
+				.with_observance(Observance::NextFreeWeekday)
+		};
+
+		Calendar::new(vec![
+			substitute("New Year's Day", Fixed(January, 1)),
+			Holiday::new("Good Friday", Easter(-2)),
+			Holiday::new("Easter Monday", Easter(1)),
+			Holiday::new("Early May bank holiday", NthWeekday(1, Mon, May)),
+			Holiday::new("Spring bank holiday", NthWeekday(-1, Mon, May)),
+			Holiday::new("Summer bank holiday", NthWeekday(-1, Mon, August)),
+			substitute("Christmas Day", Fixed(December, 25)),
+			substitute("Boxing Day", Fixed(December, 26)),
+		])
+	}
+
+	/// Nationwide public holidays in Germany
+	pub fn germany() -> Self {
+		use HolidayRule::*;
+		use Month::*;
+
+		Calendar::new(vec![
+			Holiday::new("Neujahr", Fixed(January, 1)),
+			Holiday::new("Karfreitag", Easter(-2)),
+			Holiday::new("Ostermontag", Easter(1)),
+			Holiday::new("Tag der Arbeit", Fixed(May, 1)),
+			Holiday::new("Christi Himmelfahrt", Easter(39)),
+			Holiday::new("Pfingstmontag", Easter(50)),
+			Holiday::new("Tag der Deutschen Einheit", Fixed(October, 3)),
+			Holiday::new("1. Weihnachtstag", Fixed(December, 25)),
+			Holiday::new("2. Weihnachtstag", Fixed(December, 26)),
+		])
+	}
+
+	pub fn holidays(&self) -> &[Holiday] {
+		&self.holidays
+	}
+
+	pub fn weekend(&self) -> &Weekend {
+		&self.weekend
+	}
+
+	fn is_weekend(&self, date: NaiveDate) -> bool {
+		self.weekend.contains(date.weekday())
+	}
+
+	/// The most days in a row that can be off, which bounds how far an
+	/// adjustment moves a date. Every week in such a row has its weekdays
+	/// taken by holidays, of up to two years around the new year.
+	pub(crate) fn max_days_off(&self) -> i64 {
+		let holidays = 2 * self.holidays.len() as i64;
+		let weekend = self.weekend.days_per_week() as i64;
+		let weeks = holidays / (7 - weekend) + 2;
+		holidays + weekend * weeks
+	}
+
+	/// The observed days of the holidays of the given year, which can fall
+	/// into the year before or after it
+	fn observed(&self, year: i32) -> Vec<NaiveDate> {
+		let mut actual: Vec<(NaiveDate, Observance)> = self
+			.holidays
+			.iter()
+			.filter(|holiday| holiday.first_year.is_none_or(|y| year >= y))
+			.filter_map(|holiday| {
+				let date = holiday.rule.date(year)?;
+				Some((date, holiday.observance))
+			})
+			.collect();
+		actual.sort_by_key(|(date, _)| *date);
+
+		// Substitutes skip the days already taken by other holidays, so
+		// they are resolved in order
+		let mut taken: Vec<NaiveDate> =
+			actual.iter().map(|(date, _)| *date).collect();
+		for (date, observance) in actual.iter_mut() {
+			if !self.is_weekend(*date) {
+				continue;
+			}
+
+			*date = match observance {
+				Observance::Actual => *date,
+				Observance::NearestWeekday => {
+					let next = self.next_weekday(*date, 1);
+					let previous = self.next_weekday(*date, -1);
+					if next - *date <= *date - previous {
+						next
+					} else {
+						previous
+					}
+				}
+				Observance::NextFreeWeekday => {
+					let mut next = *date + Duration::days(1);
+					while self.is_weekend(next) || taken.contains(&next) {
+						next += Duration::days(1);
+					}
+					next
+				}
+			};
+			taken.push(*date);
+		}
+
+		actual.into_iter().map(|(date, _)| date).collect()
+	}
+
+	fn next_weekday(&self, date: NaiveDate, step: i64) -> NaiveDate {
+		let mut date = date + Duration::days(step);
+		while self.is_weekend(date) {
+			date += Duration::days(step);
+		}
+		date
+	}
+
+	/// The days off in the given year, in order
+	pub fn days_in(&self, year: i32) -> Vec<NaiveDate> {
+		let mut days: Vec<NaiveDate> = (year - 1..=year + 1)
+			.flat_map(|y| self.observed(y))
+			.filter(|date| date.year() == year)
+			.collect();
+		days.sort();
+		days.dedup();
+		days
+	}
+
+	pub fn contains(&self, date: &NaiveDate) -> bool {
+		self.days_in(date.year()).contains(date)
+	}
+
+	/// Whether the date is a weekday and not a holiday
+	pub fn is_business_day(&self, date: &NaiveDate) -> bool {
+		!self.is_weekend(*date) && !self.contains(date)
+	}
+
+	fn next_business_day(&self, date: NaiveDate, step: i64) -> NaiveDate {
+		let mut date = date + Duration::days(step);
+		while !self.is_business_day(&date) {
+			date += Duration::days(step);
+		}
+		date
+	}
+
+	/// The date the given number of business days after the date, or
+	/// before it when negative
+	pub fn add_business_days(&self, date: NaiveDate, days: i64) -> NaiveDate {
+		let step = days.signum();
+		(0..days.abs()).fold(date, |date, _| self.next_business_day(date, step))
+	}
+
+	/// Move a date that isn't a business day according to the adjustment
+	pub fn adjust_date(
+		&self,
+		date: NaiveDate,
+		adjustment: Adjustment,
+	) -> NaiveDate {
+		if self.is_business_day(&date) {
+			return date;
+		}
+
+		match adjustment {
+			Adjustment::Following => self.next_business_day(date, 1),
+			Adjustment::Preceding => self.next_business_day(date, -1),
+			Adjustment::ModifiedFollowing => {
+				let next = self.next_business_day(date, 1);
+				if next.month() == date.month() {
+					next
+				} else {
+					self.next_business_day(date, -1)
+				}
+			}
+			Adjustment::Nearest => {
+				let next = self.next_business_day(date, 1);
+				let previous = self.next_business_day(date, -1);
+				if next - date <= date - previous {
+					next
+				} else {
+					previous
+				}
+			}
+		}
+	}
+}
+
+impl Timeline for Calendar {
+	fn duration_hint(&self) -> Duration {
+		Duration::days(1)
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		let calendar = self.clone();
+		let end = interval.to.map(|to| to.date_naive());
+
+		let days = (interval.from.year()..NaiveDate::MAX.year())
+			.flat_map(move |year| calendar.days_in(year))
+			.take_while(move |date| end.is_none_or(|end| *date <= end))
+			.filter_map(move |date| whole_day(date, &interval));
+
+		Box::new(days)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn days(calendar: Calendar, year: i32) -> Vec<String> {
+		calendar
+			.days_in(year)
+			.iter()
+			.map(|date| date.to_string())
+			.collect()
+	}
+
+	#[test]
+	fn easter_sundays() {
+		let easter: Vec<String> = [2019, 2024, 2038]
+			.iter()
+			.map(|year| easter(*year).unwrap().to_string())
+			.collect();
+		assert_eq!(easter, vec!["2019-04-21", "2024-03-31", "2038-04-25"]);
+	}
+
+	#[test]
+	fn us_federal_holidays_on_weekends() {
+		// New Year's Day 2022 is a saturday and observed in 2021
+		assert_eq!(
+			days(Calendar::us_federal(), 2021),
+			vec![
+				"2021-01-01",
+				"2021-01-18",
+				"2021-02-15",
+				"2021-05-31",
+				"2021-06-18",
+				"2021-07-05",
+				"2021-09-06",
+				"2021-10-11",
+				"2021-11-11",
+				"2021-11-25",
+				"2021-12-24",
+				"2021-12-31",
+			]
+		);
+	}
+
+	#[test]
+	fn holidays_around_another_weekend() {
+		use HolidayRule::*;
+
+		let calendar = Calendar::new(vec![
+			Holiday::new("Friday", Fixed(Month::January, 3))
+				.with_observance(Observance::NearestWeekday),
+			Holiday::new("Saturday", Fixed(Month::January, 4))
+				.with_observance(Observance::NearestWeekday),
+		])
+		.with_weekend(Weekend::fri_sat());
+
+		// Fridays move to the thursday before and saturdays to the sunday
+		// after
+		assert_eq!(
+			days(calendar.clone(), 2020),
+			vec!["2020-01-02", "2020-01-05"]
+		);
+		assert!(!calendar.is_business_day(&"2020-01-03".parse().unwrap()));
+		assert_eq!(
+			calendar.add_business_days("2020-01-01".parse().unwrap(), 2),
+			"2020-01-07".parse::<NaiveDate>().unwrap()
+		);
+	}
+
+	#[test]
+	fn adjust_to_business_days() {
+		let calendar = Calendar::us_federal();
+		let adjust = |date: &str, adjustment| {
+			calendar
+				.adjust_date(date.parse().unwrap(), adjustment)
+				.to_string()
+		};
+
+		// 2021-07-05 is the observed independence day
+		assert_eq!(adjust("2021-07-03", Adjustment::Following), "2021-07-06");
+		assert_eq!(adjust("2021-07-04", Adjustment::Preceding), "2021-07-02");
+		assert_eq!(adjust("2021-07-04", Adjustment::Nearest), "2021-07-06");
+		assert_eq!(
+			adjust("2021-07-31", Adjustment::ModifiedFollowing),
+			"2021-07-30"
+		);
+		assert_eq!(adjust("2021-07-07", Adjustment::Preceding), "2021-07-07");
+	}
+
+	#[test]
+	fn substitute_bank_holidays() {
+		assert_eq!(
+			days(Calendar::england_and_wales(), 2021)[5..],
+			["2021-08-30", "2021-12-27", "2021-12-28"]
+		);
+		assert_eq!(
+			days(Calendar::england_and_wales(), 2022)[6..],
+			["2022-12-26", "2022-12-27"]
+		);
+	}
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Timelike};
+
+use crate::interval::{Interval, Timeline};
+use crate::types::Dimension;
+use crate::zone::Zone;
+
+/// A set of minutes repeated every hour, like :00 and :30
+#[derive(Debug, PartialEq)]
+pub struct MinuteOfHour(Vec<u32>);
+
+impl MinuteOfHour {
+	pub fn new(mut minutes: Vec<u32>) -> Self {
+		minutes.sort();
+		minutes.dedup();
+		MinuteOfHour(minutes)
+	}
+}
+
+impl Timeline for MinuteOfHour {
+	fn duration_hint(&self) -> Duration {
+		Duration::hours(1)
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		let offsets = self.0.iter().map(|m| Duration::minutes(*m as i64));
+		Box::new(ClockIterator::new(
+			Duration::hours(1),
+			offsets.collect(),
+			interval,
+		))
+	}
+
+	fn cycle(&self) -> Option<Dimension> {
+		Some(Dimension::Hour)
+	}
+}
+
+/// A set of seconds repeated every minute
+#[derive(Debug, PartialEq)]
+pub struct SecondOfMinute(Vec<u32>);
+
+impl SecondOfMinute {
+	pub fn new(mut seconds: Vec<u32>) -> Self {
+		seconds.sort();
+		seconds.dedup();
+		SecondOfMinute(seconds)
+	}
+}
+
+impl Timeline for SecondOfMinute {
+	fn duration_hint(&self) -> Duration {
+		Duration::minutes(1)
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		let offsets = self.0.iter().map(|s| Duration::seconds(*s as i64));
+		Box::new(ClockIterator::new(
+			Duration::minutes(1),
+			offsets.collect(),
+			interval,
+		))
+	}
+
+	fn cycle(&self) -> Option<Dimension> {
+		Some(Dimension::Minute)
+	}
+}
+
+/// Instants at the given offsets from the start of every wall clock hour or
+/// minute
+struct ClockIterator {
+	unit: Duration,
+	offsets: Vec<Duration>,
+	interval: Interval,
+	completed: bool,
+}
+
+impl ClockIterator {
+	fn new(unit: Duration, offsets: Vec<Duration>, interval: Interval) -> Self {
+		ClockIterator {
+			completed: offsets.is_empty(),
+			unit,
+			offsets,
+			interval,
+		}
+	}
+
+	/// The start of the hour or minute containing the wall clock time
+	fn truncate(&self, local: NaiveDateTime) -> NaiveDateTime {
+		let local = local.with_nanosecond(0).unwrap().with_second(0).unwrap();
+		if self.unit == Duration::hours(1) {
+			local.with_minute(0).unwrap()
+		} else {
+			local
+		}
+	}
+}
+
+impl Iterator for ClockIterator {
+	type Item = Interval;
+
+	fn next(&mut self) -> Option<Interval> {
+		if self.completed {
+			return None;
+		}
+
+		let zone = self.interval.from.timezone();
+		let mut start = self.truncate(self.interval.from.naive_local());
+
+		loop {
+			// Wall clock times are resolved the same way as `At` does
+			let mut candidates: Vec<DateTime<Zone>> = self
+				.offsets
+				.iter()
+				.flat_map(|offset| zone.occurrences(&(start + *offset)))
+				.filter(|datetime| *datetime >= self.interval.from)
+				.collect();
+			candidates.sort();
+
+			let datetime = match candidates.first() {
+				Some(datetime) => *datetime,
+				None => {
+					start += self.unit;
+					if self.interval.to.is_some()
+						&& zone.localize(&start) >= self.interval.to.unwrap()
+					{
+						self.completed = true;
+						return None;
+					}
+					continue;
+				}
+			};
+
+			if self.interval.to.is_some()
+				&& datetime >= self.interval.to.unwrap()
+			{
+				self.completed = true;
+				return None;
+			}
+
+			self.interval.from = datetime + Duration::nanoseconds(1);
+
+			return Some(Interval {
+				from: datetime,
+				to: Some(datetime),
+			});
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use insta::assert_debug_snapshot_matches;
+
+	#[test]
+	fn at_00_and_30() {
+		let minutes = MinuteOfHour::new(vec![30, 0]);
+
+		assert_debug_snapshot_matches!(
+			"at_00_and_30",
+			minutes
+				.iter_within(Interval::from(
+					"2019-01-01T10:07:13Z".parse().unwrap(),
+				))
+				.take(3)
+				.collect::<Vec<_>>()
+		);
+	}
+}
@@ -3,7 +3,7 @@ use std::cmp::Ordering;
 
 #[derive(Clone, Debug)]
 enum Variant {
-	Finished(usize),
+	Finished,
 	Fresh(usize, Interval),
 	Taken(usize),
 }
@@ -48,24 +48,24 @@ impl Iterator for Combine {
 			let mut indexes: Vec<(usize, usize)> = vec![];
 
 			self.state.iter().enumerate().for_each(|(i, v)| match v {
-				Variant::Finished(_) => {}
+				Variant::Finished => {}
 				Variant::Fresh(_, _) => {}
-				Variant::Taken(j) => indexes.push((i, j.clone())),
+				Variant::Taken(j) => indexes.push((i, *j)),
 			});
 
 			for (i, j) in indexes {
 				let iterator = self.iterators.get_mut(j).unwrap();
 				self.state[i] = match iterator.next() {
 					Some(interval) => Variant::Fresh(j, interval),
-					None => Variant::Finished(j),
+					None => Variant::Finished,
 				}
 			}
 
 			self.state.sort_by(|a, b| match a {
-				Variant::Finished(_) => Ordering::Less,
+				Variant::Finished => Ordering::Less,
 				Variant::Taken(_) => Ordering::Less,
 				Variant::Fresh(_, a) => match b {
-					Variant::Finished(_) => Ordering::Greater,
+					Variant::Finished => Ordering::Greater,
 					Variant::Taken(_) => Ordering::Greater,
 					Variant::Fresh(_, b) => {
 						if a.from > b.from {
@@ -73,12 +73,10 @@ impl Iterator for Combine {
 						} else if a.from < b.from {
 							Ordering::Less
 						} else {
-							if a.to.is_none() {
-								Ordering::Greater
-							} else if b.to.is_none() {
-								Ordering::Less
-							} else {
-								a.to.unwrap().cmp(&b.to.unwrap())
+							match (a.to, b.to) {
+								(None, _) => Ordering::Greater,
+								(_, None) => Ordering::Less,
+								(Some(a), Some(b)) => a.cmp(&b),
 							}
 						}
 					}
@@ -88,13 +86,13 @@ impl Iterator for Combine {
 			let mut result: Option<Interval> = None;
 			for v in &mut self.state {
 				match v {
-					Variant::Finished(_) => {}
+					Variant::Finished => {}
 					Variant::Taken(_) => {}
 					Variant::Fresh(i, _) => {
-						let mut tmp = Variant::Taken(i.clone());
+						let mut tmp = Variant::Taken(*i);
 						std::mem::swap(v, &mut tmp);
 						match tmp {
-							Variant::Finished(_) => {}
+							Variant::Finished => {}
 							Variant::Taken(_) => {}
 							Variant::Fresh(_, v) => result = Some(v),
 						}
@@ -103,30 +101,33 @@ impl Iterator for Combine {
 				}
 			}
 
-			if result.is_some() {
-				let interval = result.as_mut().unwrap();
-				if interval.to.is_none() {
+			match result.as_mut() {
+				Some(interval) => match interval.to {
 					// If our current interval does not have an end we don't need to produce
 					// anything else because it would overlap.
-					self.completed = true;
-					return result;
-				} else {
-					if self.prev.is_some() {
-						let to = self.prev.as_ref().unwrap().to.unwrap();
-						if interval.from < to {
-							interval.from = to.clone();
-							if interval.from > interval.to.unwrap() {
-								// We consumed the whole interval
-								continue;
+					None => {
+						self.completed = true;
+						return result;
+					}
+					Some(interval_to) => {
+						if let Some(prev) = &self.prev {
+							let to = prev.to.unwrap();
+							if interval.from < to {
+								interval.from = to;
+								if interval.from >= interval_to {
+									// We consumed the whole interval
+									continue;
+								}
 							}
 						}
+						self.prev = result.clone();
+						return result;
 					}
-					self.prev = result.clone();
-					return result;
+				},
+				None => {
+					self.completed = true;
+					return None;
 				}
-			} else {
-				self.completed = true;
-				return None;
 			}
 		}
 	}
@@ -0,0 +1,113 @@
+use chrono::Duration;
+
+use crate::interval::{Interval, Timeline};
+use crate::merge::Merge;
+
+/// Time not covered by the timeline within the interval it is iterated in
+#[derive(Debug)]
+pub struct Complement(Box<dyn Timeline>);
+
+impl Complement {
+	pub fn new(timeline: Box<dyn Timeline>) -> Self {
+		Complement(timeline)
+	}
+}
+
+impl Timeline for Complement {
+	fn duration_hint(&self) -> Duration {
+		self.0.duration_hint()
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		Box::new(ComplementIterator {
+			base: Merge::new(self.0.iter_within(interval.clone()), usize::MAX),
+			interval: Some(interval),
+		})
+	}
+}
+
+struct ComplementIterator {
+	base: Merge<Box<dyn Iterator<Item = Interval>>>,
+	/// The part of the bounding interval that is not processed yet
+	interval: Option<Interval>,
+}
+
+impl Iterator for ComplementIterator {
+	type Item = Interval;
+
+	fn next(&mut self) -> Option<Interval> {
+		loop {
+			let interval = self.interval.as_mut()?;
+
+			let next = match self.base.next() {
+				Some(next) => next,
+				None => return self.interval.take(),
+			};
+
+			if next.is_instant() {
+				continue;
+			}
+
+			let gap = if next.from > interval.from {
+				let end = match interval.to {
+					Some(to) => next.from.min(to),
+					None => next.from,
+				};
+
+				Some(Interval {
+					from: interval.from,
+					to: Some(end),
+				})
+			} else {
+				None
+			};
+
+			match next.to {
+				Some(to) if to > interval.from => interval.from = to,
+				Some(_) => {}
+				None => self.interval = None,
+			}
+
+			if let Some(interval) = &self.interval {
+				if interval.to.is_some()
+					&& interval.from >= interval.to.unwrap()
+				{
+					self.interval = None;
+				}
+			}
+
+			if gap.is_some() {
+				return gap;
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::timeframe::TimeFrame;
+	use chrono::NaiveTime;
+	use insta::assert_debug_snapshot_matches;
+
+	#[test]
+	fn outside_business_hours() {
+		let outside = Complement::new(Box::new(TimeFrame::new(
+			NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+			NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+		)));
+
+		assert_debug_snapshot_matches!(
+			"outside_business_hours",
+			outside
+				.iter_within(
+					Interval::from("2019-01-01T00:00:00Z".parse().unwrap())
+						.until("2019-01-03T00:00:00Z".parse().unwrap())
+				)
+				.collect::<Vec<_>>()
+		);
+	}
+}
@@ -0,0 +1,204 @@
+use chrono::{
+	DateTime, Datelike, Duration, Month, NaiveDate, NaiveDateTime, NaiveTime,
+	Utc,
+};
+
+use crate::calendar::Calendar;
+use crate::interval::{Interval, Timeline};
+use crate::utils::{end_of, Of};
+use crate::zone::Zone;
+
+/// The whole day on the given date, clipped to the interval
+pub fn whole_day(date: NaiveDate, interval: &Interval) -> Option<Interval> {
+	let start = interval
+		.from
+		.timezone()
+		.localize(&date.and_time(NaiveTime::MIN));
+	let from = start.max(interval.from);
+	let to = match interval.to {
+		Some(to) => end_of(Of::Day, &start).min(to),
+		None => end_of(Of::Day, &start),
+	};
+
+	if from < to {
+		Some(Interval { from, to: Some(to) })
+	} else {
+		None
+	}
+}
+
+/// A set of whole days on the calendar
+#[derive(Debug, PartialEq)]
+pub struct Dates(Vec<NaiveDate>);
+
+impl Dates {
+	pub fn new(mut dates: Vec<NaiveDate>) -> Self {
+		dates.sort();
+		dates.dedup();
+		Dates(dates)
+	}
+
+	pub fn contains(&self, date: &NaiveDate) -> bool {
+		self.0.binary_search(date).is_ok()
+	}
+}
+
+impl Timeline for Dates {
+	fn duration_hint(&self) -> Duration {
+		Duration::days(1)
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		let days = self
+			.0
+			.clone()
+			.into_iter()
+			.filter_map(move |date| whole_day(date, &interval));
+
+		Box::new(days)
+	}
+}
+
+/// A single instant
+#[derive(Debug, PartialEq)]
+pub struct Moment(DateTime<Utc>);
+
+impl Moment {
+	pub fn new(at: DateTime<Utc>) -> Self {
+		Moment(at)
+	}
+}
+
+impl Timeline for Moment {
+	fn duration_hint(&self) -> Duration {
+		Duration::zero()
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		let at = self.0.with_timezone(&interval.from.timezone());
+		let within = at >= interval.from
+			&& interval.to.map(|to| at < to).unwrap_or(true);
+
+		Box::new(
+			Some(Interval {
+				from: at,
+				to: Some(at),
+			})
+			.filter(|_| within)
+			.into_iter(),
+		)
+	}
+}
+
+/// A date or a wall clock time added to or removed from a schedule, like
+/// `RDATE` and `EXDATE` in RFC 5545
+#[derive(Debug, Clone, PartialEq)]
+pub enum DateSpec {
+	/// A whole day
+	Date(NaiveDate),
+	/// A single wall clock time
+	DateTime(NaiveDateTime),
+	/// The same day of the same month every year, like christmas
+	Annual(Month, u32),
+	/// The days off of a holiday calendar
+	Holidays(Calendar),
+}
+
+impl DateSpec {
+	/// Whether an occurrence starting at the given instant falls on it
+	pub fn matches(&self, at: &DateTime<Zone>) -> bool {
+		match self {
+			DateSpec::Date(date) => at.date_naive() == *date,
+			DateSpec::DateTime(datetime) => at.naive_local() == *datetime,
+			DateSpec::Annual(month, day) => {
+				at.month() == month.number_from_month() && at.day() == *day
+			}
+			DateSpec::Holidays(calendar) => calendar.contains(&at.date_naive()),
+		}
+	}
+}
+
+impl Timeline for DateSpec {
+	fn duration_hint(&self) -> Duration {
+		match self {
+			DateSpec::DateTime(_) => Duration::zero(),
+			DateSpec::Date(_)
+			| DateSpec::Annual(_, _)
+			| DateSpec::Holidays(_) => Duration::days(1),
+		}
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		match *self {
+			DateSpec::Holidays(ref calendar) => calendar.iter_within(interval),
+			DateSpec::Date(date) => {
+				Box::new(whole_day(date, &interval).into_iter())
+			}
+			DateSpec::DateTime(datetime) => {
+				let instants = interval
+					.from
+					.timezone()
+					.occurrences(&datetime)
+					.into_iter()
+					.filter(move |at| {
+						*at >= interval.from
+							&& interval.to.map(|to| *at < to).unwrap_or(true)
+					})
+					.map(|at| Interval {
+						from: at,
+						to: Some(at),
+					});
+
+				Box::new(instants)
+			}
+			DateSpec::Annual(month, day) => {
+				let month = month.number_from_month();
+				let start = interval.from.year();
+				let end = interval.to;
+
+				// Years without the day, like february 29th in common
+				// years, are skipped
+				let days = (start..=NaiveDate::MAX.year())
+					.filter_map(move |year| {
+						NaiveDate::from_ymd_opt(year, month, day)
+					})
+					.take_while(move |date| match end {
+						Some(end) => *date <= end.date_naive(),
+						None => true,
+					})
+					.filter_map(move |date| whole_day(date, &interval));
+
+				Box::new(days)
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono_tz::Tz;
+
+	#[test]
+	fn christmas_every_year() {
+		let interval = Interval::from("2019-12-25T12:00:00Z".parse().unwrap())
+			.until("2021-12-25T00:00:00Z".parse().unwrap())
+			.with_timezone(Tz::UTC);
+
+		let days: Vec<String> = DateSpec::Annual(Month::December, 25)
+			.iter_within(interval)
+			.map(|day| day.from.naive_local().to_string())
+			.collect();
+
+		assert_eq!(days, vec!["2019-12-25 12:00:00", "2020-12-25 00:00:00"]);
+	}
+}
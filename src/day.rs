@@ -0,0 +1,197 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+
+use crate::interval::Interval;
+use crate::utils::{end_of, start_of, Of};
+
+/// The day of the period that is the nth given weekday in it, counting
+/// from the end when `nth` is negative.
+fn nth_weekday(
+	nth: i32,
+	weekday: Weekday,
+	start: NaiveDate,
+	end: NaiveDate,
+) -> Option<NaiveDate> {
+	let day = if nth > 0 {
+		let first = (7 + weekday.num_days_from_monday()
+			- start.weekday().num_days_from_monday())
+			% 7;
+		start + Duration::days(i64::from(first) + 7 * i64::from(nth - 1))
+	} else {
+		let last_day = end - Duration::days(1);
+		let last = (7 + last_day.weekday().num_days_from_monday()
+			- weekday.num_days_from_monday())
+			% 7;
+		last_day - Duration::days(i64::from(last) + 7 * i64::from(-nth - 1))
+	};
+
+	if day >= start && day < end {
+		Some(day)
+	} else {
+		None
+	}
+}
+
+/// The most occurrences of a weekday a period can have
+pub fn max_nth(of: Of) -> i32 {
+	match of {
+		Of::Day => 1,
+		Of::Month => 5,
+		Of::Quarter => 14,
+		Of::Year => 53,
+	}
+}
+
+/// What to do with days that don't exist in short months, like the 31st
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShortMonth {
+	/// Don't produce a day in such months
+	Skip,
+	/// Use the last day of the month, or the first one for days counted
+	/// from the end
+	Clamp,
+	/// Move forward to the first day that exists, which is the first day of
+	/// the next month, or of the same month for days counted from the end
+	Roll,
+}
+
+/// The nth day of the month counted from the end when `nth` is negative
+fn day_of_month(
+	nth: i32,
+	short: ShortMonth,
+	start: NaiveDate,
+	end: NaiveDate,
+) -> Option<NaiveDate> {
+	let days = (end - start).num_days() as i32;
+	let day = if nth > 0 { nth - 1 } else { days + nth };
+
+	let day = if day >= 0 && day < days {
+		day
+	} else {
+		match short {
+			ShortMonth::Skip => return None,
+			ShortMonth::Clamp if nth > 0 => days - 1,
+			ShortMonth::Roll if nth > 0 => days,
+			ShortMonth::Clamp | ShortMonth::Roll => 0,
+		}
+	};
+
+	Some(start + Duration::days(i64::from(day)))
+}
+
+enum Rule {
+	NthWeekday(i32, Weekday),
+	DayOfMonth(i32, ShortMonth),
+}
+
+/// An iterator over a single day picked from every month, quarter or year
+pub struct DayIterator {
+	rule: Rule,
+	of: Of,
+	interval: Interval,
+	completed: bool,
+}
+
+impl DayIterator {
+	/// The nth weekday of every period
+	pub fn nth_weekday(
+		nth: i32,
+		weekday: Weekday,
+		of: Of,
+		interval: Interval,
+	) -> Self {
+		DayIterator {
+			completed: nth == 0 || nth.abs() > max_nth(of),
+			rule: Rule::NthWeekday(nth, weekday),
+			of,
+			interval,
+		}
+	}
+
+	/// The nth day of every month
+	pub fn day_of_month(
+		nth: i32,
+		short: ShortMonth,
+		interval: Interval,
+	) -> Self {
+		DayIterator {
+			completed: nth == 0 || nth.abs() > 31,
+			rule: Rule::DayOfMonth(nth, short),
+			of: Of::Month,
+			interval,
+		}
+	}
+
+	fn pick(&self, start: NaiveDate, end: NaiveDate) -> Option<NaiveDate> {
+		match self.rule {
+			Rule::NthWeekday(nth, weekday) => {
+				nth_weekday(nth, weekday, start, end)
+			}
+			Rule::DayOfMonth(nth, short) => {
+				day_of_month(nth, short, start, end)
+			}
+		}
+	}
+}
+
+impl Iterator for DayIterator {
+	type Item = Interval;
+
+	fn next(&mut self) -> Option<Interval> {
+		if self.completed {
+			return None;
+		}
+
+		loop {
+			let from = self.interval.from;
+			let zone = from.timezone();
+
+			if self.interval.to.is_some() && from >= self.interval.to.unwrap() {
+				self.completed = true;
+				return None;
+			}
+
+			let start = start_of(self.of, &from);
+			let end = end_of(self.of, &from);
+
+			let day = self.pick(start.date_naive(), end.date_naive()).and_then(
+				|day| zone.period_start(&day.and_time(NaiveTime::MIN)),
+			);
+
+			let day = match day {
+				Some(day) if end_of(Of::Day, &day) > from => day,
+				// The day is missing, skipped or already passed in this
+				// period
+				_ => {
+					self.interval.from = end;
+					continue;
+				}
+			};
+
+			let from = from.max(day);
+			if self.interval.to.is_some() && from >= self.interval.to.unwrap() {
+				self.completed = true;
+				return None;
+			}
+
+			let end_of_day = end_of(Of::Day, &day);
+
+			if self.interval.to.is_some()
+				&& self.interval.to.unwrap() < end_of_day
+			{
+				self.completed = true;
+				return Some(Interval {
+					from,
+					to: self.interval.to,
+				});
+			}
+
+			// A day rolled over to the next period must not be produced twice
+			self.interval.from = end.max(end_of_day);
+
+			return Some(Interval {
+				from,
+				to: Some(end_of_day),
+			});
+		}
+	}
+}
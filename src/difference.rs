@@ -0,0 +1,141 @@
+use chrono::Duration;
+use std::iter::Peekable;
+
+use crate::interval::{Interval, Timeline};
+use crate::merge::Merge;
+
+type Stream = Peekable<Merge<Box<dyn Iterator<Item = Interval>>>>;
+
+/// Time covered by the first timeline but not by the second one
+#[derive(Debug)]
+pub struct Difference(Box<dyn Timeline>, Box<dyn Timeline>);
+
+impl Difference {
+	pub fn new(base: Box<dyn Timeline>, except: Box<dyn Timeline>) -> Self {
+		Difference(base, except)
+	}
+}
+
+impl Timeline for Difference {
+	fn duration_hint(&self) -> Duration {
+		self.0.duration_hint()
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		Box::new(DifferenceIterator {
+			base: self.0.iter_within(interval.clone()),
+			except: Merge::new(self.1.iter_within(interval), usize::MAX)
+				.peekable(),
+			current: None,
+		})
+	}
+}
+
+struct DifferenceIterator {
+	base: Box<dyn Iterator<Item = Interval>>,
+	except: Stream,
+	current: Option<Interval>,
+}
+
+impl Iterator for DifferenceIterator {
+	type Item = Interval;
+
+	fn next(&mut self) -> Option<Interval> {
+		loop {
+			let mut current = match self.current.take() {
+				Some(current) => current,
+				None => self.base.next()?,
+			};
+
+			// Skip everything that is over before the current interval
+			while let Some(except) = self.except.peek() {
+				if except.ends_before(&current) {
+					self.except.next();
+				} else {
+					break;
+				}
+			}
+
+			let except = match self.except.peek() {
+				None => return Some(current),
+				Some(except) => except,
+			};
+
+			if current.is_instant() {
+				if except.from <= current.from {
+					// The instant is covered
+					continue;
+				}
+				return Some(current);
+			}
+
+			if except.is_instant() {
+				// Instants don't take any time away from an interval
+				if current.to.is_none() || except.from < current.to.unwrap() {
+					self.except.next();
+					self.current = Some(current);
+					continue;
+				}
+				return Some(current);
+			}
+
+			if current.to.is_some() && except.from >= current.to.unwrap() {
+				return Some(current);
+			}
+
+			if except.from <= current.from {
+				match except.to {
+					Some(to)
+						if current.to.is_none() || to < current.to.unwrap() =>
+					{
+						// Keep what remains after the excluded interval
+						current.from = to;
+						self.current = Some(current);
+					}
+					// The whole interval is excluded
+					_ => {}
+				}
+				continue;
+			}
+
+			// Produce the part before the excluded interval and keep the rest
+			let head = Interval {
+				from: current.from,
+				to: Some(except.from),
+			};
+			current.from = except.from;
+			self.current = Some(current);
+			return Some(head);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::period::Period;
+	use crate::weekday::Weekend;
+	use chrono::Weekday;
+	use insta::assert_debug_snapshot_matches;
+
+	#[test]
+	fn weekdays_except_wednesday() {
+		let difference = Difference::new(
+			Box::new(Period::Weekday(Weekend::default())),
+			Box::new(Weekday::Wed),
+		);
+
+		assert_debug_snapshot_matches!(
+			"weekdays_except_wednesday",
+			difference
+				.iter_within(Interval::from(
+					"2019-01-01T00:00:00Z".parse().unwrap()
+				))
+				.take(5)
+				.collect::<Vec<_>>()
+		);
+	}
+}
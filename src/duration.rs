@@ -1,22 +1,98 @@
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+
 use crate::interval::{Interval, Timeline};
-use chrono::Duration;
+use crate::zone::Zone;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime};
+
+/// Where the steps of a fixed duration recurrence begin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Anchor {
+	/// At the start of the interval it is iterated within
+	Start,
+	/// At wall clock boundaries, so 15 minutes are :00, :15, :30 and :45
+	/// and weeks start on monday
+	Clock,
+	/// At the given wall clock time and every step before or after it
+	Epoch(NaiveDateTime),
+}
+
+/// Iterate over steps of the duration that begin at the anchor
+pub fn iter_anchored(
+	duration: Duration,
+	anchor: Anchor,
+	interval: Interval,
+) -> Box<dyn Iterator<Item = Interval>> {
+	let origin = match anchor {
+		Anchor::Start => return duration.iter_within(interval),
+		// 1970-01-05 is a monday
+		Anchor::Clock => NaiveDate::from_ymd_opt(1970, 1, 5)
+			.unwrap()
+			.and_hms_opt(0, 0, 0)
+			.unwrap(),
+		Anchor::Epoch(origin) => origin,
+	};
+
+	let step = nanos(duration);
+	if step <= 0 {
+		return Box::new(std::iter::empty());
+	}
+
+	// The first step that doesn't begin before the interval
+	let since = nanos(interval.from.naive_local() - origin);
+	let index = since.div_euclid(step) + i128::from(since.rem_euclid(step) > 0);
+
+	Box::new(AlignedIterator {
+		origin,
+		step,
+		index,
+		interval,
+		next: None,
+		pending: VecDeque::new(),
+	})
+}
+
+pub fn nanos(duration: Duration) -> i128 {
+	i128::from(duration.num_seconds()) * 1_000_000_000
+		+ i128::from(duration.subsec_nanos())
+}
+
+fn from_nanos(nanos: i128) -> Option<Duration> {
+	let seconds = i64::try_from(nanos.div_euclid(1_000_000_000)).ok()?;
+	Duration::try_seconds(seconds)?.checked_add(&Duration::nanoseconds(
+		nanos.rem_euclid(1_000_000_000) as i64,
+	))
+}
 
 impl Timeline for Duration {
 	fn duration_hint(&self) -> Duration {
-		self.clone()
+		*self
 	}
 
 	fn iter_within(
 		&self,
 		interval: Interval,
 	) -> Box<dyn Iterator<Item = Interval>> {
+		// Steps of a day or more keep their time of day, like aligned ones
+		if *self >= Duration::days(1) {
+			return Box::new(AlignedIterator {
+				origin: interval.from.naive_local(),
+				step: nanos(*self),
+				index: 0,
+				interval,
+				next: None,
+				pending: VecDeque::new(),
+			});
+		}
+
 		Box::new(DurationIterator {
-			duration: self.clone(),
+			duration: *self,
 			interval,
 		})
 	}
 }
 
+/// Steps shorter than a day, in absolute time from the start
 struct DurationIterator {
 	duration: Duration,
 	interval: Interval,
@@ -25,16 +101,240 @@ struct DurationIterator {
 impl Iterator for DurationIterator {
 	type Item = Interval;
 	fn next(&mut self) -> Option<Interval> {
-		let from = self.interval.from.clone();
-		let to = self.interval.from + self.duration;
+		// Steps past the range of dates end the iteration
+		let from = self.interval.from;
+		let mut to = from.checked_add_signed(self.duration)?;
 
-		if self.interval.to.is_some()
-			&& to > *self.interval.to.as_ref().unwrap()
-		{
-			return None;
+		// The last step is cut at the end of the interval
+		if let Some(end) = self.interval.to {
+			if from >= end {
+				return None;
+			}
+			to = to.min(end);
 		}
 
 		self.interval.from = to;
 		Some(Interval { from, to: Some(to) })
 	}
 }
+
+/// Steps are aligned on the wall clock. Steps of a day or more are counted
+/// on the wall clock too so they keep their time of day across daylight
+/// saving transitions, shorter ones advance in absolute time from the first
+/// aligned step so they never go back or skip a repeated hour.
+struct AlignedIterator {
+	origin: NaiveDateTime,
+	step: i128,
+	index: i128,
+	interval: Interval,
+	next: Option<DateTime<Zone>>,
+	pending: VecDeque<Interval>,
+}
+
+impl AlignedIterator {
+	/// The wall clock time the step with the given index begins, unless
+	/// that's past the range of dates
+	fn wall(&self, index: i128) -> Option<NaiveDateTime> {
+		let offset = from_nanos(index.checked_mul(self.step)?)?;
+		self.origin.checked_add_signed(offset)
+	}
+
+	/// The next step on the wall clock, the policy of the zone decides
+	/// whether a step starting at a skipped or repeated time happens once,
+	/// twice or not at all
+	fn next_on_the_wall(&mut self) -> Option<Interval> {
+		let zone = self.interval.from.timezone();
+		while self.pending.is_empty() {
+			let (from, to) =
+				(self.wall(self.index)?, self.wall(self.index + 1)?);
+			if self
+				.interval
+				.to
+				.is_some_and(|end| zone.localize(&from) >= end)
+			{
+				return None;
+			}
+
+			// The last step is cut at the end of the interval
+			let start = self.interval.from;
+			let end = Interval {
+				from: start,
+				to: self.interval.to,
+			};
+			self.pending.extend(
+				zone.windows(&from, &to)
+					.into_iter()
+					.filter(|window| window.from >= start)
+					.filter_map(|window| window.intersect(&end)),
+			);
+			self.index += 1;
+		}
+
+		self.pending.pop_front()
+	}
+}
+
+impl Iterator for AlignedIterator {
+	type Item = Interval;
+
+	fn next(&mut self) -> Option<Interval> {
+		if self.step >= nanos(Duration::days(1)) {
+			return self.next_on_the_wall();
+		}
+
+		let zone = self.interval.from.timezone();
+		let from = match self.next {
+			Some(next) => next,
+			None => zone.localize(&self.wall(self.index)?),
+		};
+		let mut to = from.checked_add_signed(from_nanos(self.step)?)?;
+
+		if let Some(end) = self.interval.to {
+			if from >= end {
+				return None;
+			}
+			to = to.min(end);
+		}
+
+		self.next = Some(to);
+		Some(Interval { from, to: Some(to) })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::Utc;
+
+	#[test]
+	fn every_250_ms() {
+		let steps: Vec<Interval> = Duration::milliseconds(250)
+			.iter_within(
+				Interval::from("2019-01-01T00:00:00Z".parse().unwrap())
+					.until("2019-01-01T00:00:01Z".parse().unwrap()),
+			)
+			.collect();
+
+		assert_eq!(steps.len(), 4);
+		for pair in steps.windows(2) {
+			assert_eq!(pair[0].to, Some(pair[1].from));
+		}
+	}
+
+	#[test]
+	fn the_last_step_is_cut() {
+		let interval = Interval::from("2019-01-01T00:00:00Z".parse().unwrap())
+			.until("2019-01-03T09:00:00Z".parse().unwrap());
+		let last = |step: Duration, anchor| {
+			let last = iter_anchored(step, anchor, interval.clone())
+				.last()
+				.unwrap();
+			(last.from.format("%d %H:%M").to_string(), last.to)
+		};
+
+		for anchor in [Anchor::Start, Anchor::Clock] {
+			assert_eq!(
+				last(Duration::days(1), anchor),
+				("03 00:00".to_string(), interval.to)
+			);
+			assert_eq!(
+				last(Duration::hours(6), anchor),
+				("03 06:00".to_string(), interval.to)
+			);
+		}
+	}
+
+	#[test]
+	fn steps_past_the_last_date() {
+		let from = || Interval::from("2019-01-01T00:00:00Z".parse().unwrap());
+		let count = |step: Duration, anchor| {
+			iter_anchored(step, anchor, from()).count()
+		};
+
+		assert_eq!(count(Duration::weeks(100_000_000), Anchor::Start), 0);
+		assert_eq!(count(Duration::weeks(3_000_000), Anchor::Start), 4);
+		assert_eq!(count(Duration::weeks(100_000_000), Anchor::Clock), 0);
+	}
+
+	fn berlin(anchor: Anchor, step: Duration, from: &str) -> Vec<String> {
+		let interval = Interval::from(from.parse().unwrap())
+			.with_timezone(chrono_tz::Europe::Berlin);
+
+		iter_anchored(step, anchor, interval)
+			.take(4)
+			.map(|interval| {
+				assert!(interval.to.unwrap() > interval.from);
+				interval
+					.from
+					.with_timezone(&Utc)
+					.format("%H:%M")
+					.to_string()
+			})
+			.collect()
+	}
+
+	#[test]
+	fn on_the_clock_across_dst() {
+		let half_hour = Duration::minutes(30);
+		// Clocks go from 02:00 to 03:00 at 01:00 UTC
+		assert_eq!(
+			berlin(Anchor::Clock, half_hour, "2019-03-31T00:00:00Z"),
+			vec!["00:00", "00:30", "01:00", "01:30"]
+		);
+		// Clocks go from 03:00 back to 02:00 at 01:00 UTC
+		assert_eq!(
+			berlin(Anchor::Clock, half_hour, "2019-10-27T00:30:00Z"),
+			vec!["00:30", "01:00", "01:30", "02:00"]
+		);
+	}
+
+	#[test]
+	fn from_the_start_across_dst() {
+		// Clocks go from 02:00 to 03:00 on the 31st
+		assert_eq!(
+			berlin(Anchor::Start, Duration::days(1), "2019-03-29T09:00:00Z"),
+			vec!["09:00", "09:00", "08:00", "08:00"]
+		);
+		assert_eq!(
+			berlin(Anchor::Start, Duration::hours(1), "2019-03-31T00:00:00Z"),
+			vec!["00:00", "01:00", "02:00", "03:00"]
+		);
+	}
+
+	#[test]
+	fn from_an_epoch_across_dst() {
+		let epoch = Anchor::Epoch(
+			NaiveDate::from_ymd_opt(2019, 1, 1)
+				.unwrap()
+				.and_hms_opt(0, 10, 0)
+				.unwrap(),
+		);
+		assert_eq!(
+			berlin(epoch, Duration::hours(1), "2019-03-30T23:30:00Z"),
+			vec!["00:10", "01:10", "02:10", "03:10"]
+		);
+		assert_eq!(
+			berlin(epoch, Duration::hours(1), "2019-10-26T23:30:00Z"),
+			vec!["00:10", "01:10", "02:10", "03:10"]
+		);
+		// Days keep their time of day
+		assert_eq!(
+			berlin(epoch, Duration::days(1), "2019-03-30T12:00:00Z"),
+			vec!["23:10", "22:10", "22:10", "22:10"]
+		);
+	}
+
+	#[test]
+	fn every_15_min_on_the_clock() {
+		let steps: Vec<String> = iter_anchored(
+			Duration::minutes(15),
+			Anchor::Clock,
+			Interval::from("2019-01-01T10:07:13Z".parse().unwrap()),
+		)
+		.take(3)
+		.map(|interval| interval.from.time().to_string())
+		.collect();
+
+		assert_eq!(steps, vec!["10:15:00", "10:30:00", "10:45:00"]);
+	}
+}
@@ -1,8 +1,11 @@
-use chrono::Duration;
+use chrono::{DateTime, Duration, NaiveDateTime};
 
 use crate::combine::Combine;
+use crate::duration::Anchor;
 use crate::interval::{Interval, Timeline};
 use crate::period::Period;
+use crate::types::Dimension;
+use crate::zone::Zone;
 
 #[derive(Debug, PartialEq)]
 pub struct Every {
@@ -13,11 +16,29 @@ impl Every {
 	pub fn new(periods: Vec<Period>) -> Self {
 		Every { periods }
 	}
+
+	/// Anchor the fixed periods that begin at the start of the iteration at
+	/// the given wall clock time instead, which is where the schedule they
+	/// belong to starts
+	pub fn starting(self, start: NaiveDateTime) -> Self {
+		let periods = self
+			.periods
+			.into_iter()
+			.map(|period| match period {
+				Period::Fixed(duration, Anchor::Start) => {
+					Period::Fixed(duration, Anchor::Epoch(start))
+				}
+				period => period,
+			})
+			.collect();
+
+		Every { periods }
+	}
 }
 
 impl Timeline for Every {
 	fn duration_hint(&self) -> Duration {
-		self.periods.get(0).map(|p| p.duration_hint()).unwrap()
+		self.periods.first().map(|p| p.duration_hint()).unwrap()
 	}
 
 	fn iter_within(
@@ -31,4 +52,21 @@ impl Timeline for Every {
 				.collect(),
 		))
 	}
+
+	/// The longest cycle of the periods, if they all have one
+	fn cycle(&self) -> Option<Dimension> {
+		self.periods
+			.iter()
+			.map(|p| p.cycle())
+			.collect::<Option<Vec<_>>>()?
+			.into_iter()
+			.max()
+	}
+
+	fn step(&self, interval: &Interval) -> Option<(DateTime<Zone>, Dimension)> {
+		match self.periods.as_slice() {
+			[period] => period.step(interval),
+			_ => None,
+		}
+	}
 }
@@ -0,0 +1,508 @@
+use std::convert::TryFrom;
+use std::num::NonZeroUsize;
+
+use chrono::{DateTime, Duration, Months, NaiveDate};
+
+use crate::calendar::{Adjustment, Calendar};
+use crate::difference::Difference;
+use crate::intersection::Intersection;
+use crate::interval::{Interval, Timeline};
+use crate::merge::Merge;
+use crate::union::Union;
+use crate::zone::Zone;
+
+/// Combinators to compose timelines without dealing with iterators
+pub trait TimelineExt: Timeline + Sized + 'static {
+	/// Time covered by either of the timelines
+	fn or<T: Timeline + 'static>(self, other: T) -> Union {
+		Union::new(vec![Box::new(self), Box::new(other)])
+	}
+
+	/// Time covered by both timelines
+	fn and<T: Timeline + 'static>(self, other: T) -> Intersection {
+		Intersection::new(Box::new(self), Box::new(other))
+	}
+
+	/// Time covered by this timeline but not by the other one
+	fn except<T: Timeline + 'static>(self, other: T) -> Difference {
+		Difference::new(Box::new(self), Box::new(other))
+	}
+
+	/// Move every interval by the given duration
+	fn shift(self, by: Duration) -> Shift {
+		self.offset(Offset::Duration(by))
+	}
+
+	/// Only keep the time within the given interval
+	fn clip(self, interval: Interval) -> Clip {
+		Clip(Box::new(self), interval)
+	}
+
+	/// Merge overlapping and adjacent intervals together
+	fn merge_adjacent(self) -> MergeAdjacent {
+		MergeAdjacent(Box::new(self))
+	}
+
+	/// Make every interval last for the given duration from its start, which
+	/// turns instants into intervals
+	fn lasting(self, duration: Duration) -> Lasting {
+		Lasting(Box::new(self), duration)
+	}
+
+	/// Only keep every nth interval, starting with the first one
+	fn nth(self, n: NonZeroUsize) -> Nth {
+		Nth(Box::new(self), n)
+	}
+
+	/// Move every interval starting on a day that isn't a business day in
+	/// the calendar to a business day, keeping its time of day
+	fn adjust(self, adjustment: Adjustment, calendar: Calendar) -> Adjust {
+		Adjust(Box::new(self), adjustment, calendar)
+	}
+
+	/// Move every interval by the given offset, counting days and months on
+	/// the calendar
+	fn offset(self, offset: Offset) -> Shift {
+		Shift(Box::new(self), offset)
+	}
+}
+
+impl<T: Timeline + 'static> TimelineExt for T {}
+
+#[derive(Debug)]
+pub struct Clip(Box<dyn Timeline>, Interval);
+
+impl Timeline for Clip {
+	fn duration_hint(&self) -> Duration {
+		self.0.duration_hint()
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		let clip = match interval.intersect(&self.1) {
+			Some(clip) => clip,
+			None => return Box::new(std::iter::empty()),
+		};
+
+		let end = clip.to;
+
+		// Intervals crossing the bounds are trimmed rather than skipped, so
+		// the timeline is resolved within the whole requested interval
+		Box::new(
+			self.0
+				.iter_within(interval)
+				.take_while(move |interval| match end {
+					Some(end) => interval.from < end,
+					None => true,
+				})
+				.filter_map(move |interval| interval.intersect(&clip)),
+		)
+	}
+}
+
+#[derive(Debug)]
+pub struct MergeAdjacent(Box<dyn Timeline>);
+
+impl Timeline for MergeAdjacent {
+	fn duration_hint(&self) -> Duration {
+		self.0.duration_hint()
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		Box::new(Merge::new(self.0.iter_within(interval), usize::MAX))
+	}
+}
+
+#[derive(Debug)]
+pub struct Lasting(Box<dyn Timeline>, Duration);
+
+impl Timeline for Lasting {
+	fn duration_hint(&self) -> Duration {
+		self.0.duration_hint()
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		let duration = self.1;
+		Box::new(self.0.iter_within(interval).map(move |interval| Interval {
+			to: Some(interval.from + duration),
+			..interval
+		}))
+	}
+}
+
+#[derive(Debug)]
+pub struct Nth(Box<dyn Timeline>, NonZeroUsize);
+
+impl Timeline for Nth {
+	fn duration_hint(&self) -> Duration {
+		i32::try_from(self.1.get())
+			.ok()
+			.and_then(|n| self.0.duration_hint().checked_mul(n))
+			.unwrap_or(Duration::MAX)
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		Box::new(self.0.iter_within(interval).step_by(self.1.get()))
+	}
+}
+
+/// Move an interval to another day keeping its wall clock times, so its end
+/// moves by as many days on the wall clock. The policy of the zone decides
+/// whether it happens once, twice or not at all when the time is skipped or
+/// repeated on that day.
+pub(crate) fn move_to_day(interval: Interval, day: NaiveDate) -> Vec<Interval> {
+	let zone = interval.from.timezone();
+	let local = interval.from.naive_local();
+	let days = day - local.date();
+
+	zone.occurrences(&day.and_time(local.time()))
+		.into_iter()
+		.map(|from| {
+			// Ends past the range of dates leave the interval open
+			let to = match interval.to {
+				_ if interval.is_instant() => Some(from),
+				Some(to) => to
+					.naive_local()
+					.checked_add_signed(days)
+					.map(|to| zone.localize(&to).max(from)),
+				None => None,
+			};
+			Interval { from, to }
+		})
+		.collect()
+}
+
+/// Move the intervals produced within a wider interval and keep the ones
+/// within the given one, cutting the one in progress at its start. Moving
+/// keeps them in order but can put several at the same start, which are
+/// only produced once. Intervals that can't be moved end up out of the
+/// range of dates, which ends the iteration.
+fn move_within<F>(
+	intervals: Box<dyn Iterator<Item = Interval>>,
+	interval: &Interval,
+	f: F,
+) -> Box<dyn Iterator<Item = Interval>>
+where
+	F: FnMut(Interval) -> Option<Vec<Interval>> + 'static,
+{
+	let since = Interval {
+		from: interval.from,
+		to: None,
+	};
+	let to = interval.to;
+	let mut last: Option<DateTime<Zone>> = None;
+
+	Box::new(
+		intervals
+			.map_while(f)
+			.flatten()
+			.take_while(move |interval| to.is_none_or(|to| interval.from < to))
+			.filter_map(move |interval| interval.intersect(&since))
+			.filter(move |interval| {
+				let fresh = last.is_none_or(|last| interval.from > last);
+				if fresh {
+					last = Some(interval.from);
+				}
+				fresh
+			}),
+	)
+}
+
+/// Widen the interval by the margin on both sides, as far as there are
+/// dates
+fn widen(interval: &Interval, margin: Duration) -> Interval {
+	Interval {
+		from: interval
+			.from
+			.checked_sub_signed(margin)
+			.unwrap_or(interval.from),
+		to: interval.to.and_then(|to| to.checked_add_signed(margin)),
+	}
+}
+
+#[derive(Debug)]
+pub struct Adjust(Box<dyn Timeline>, Adjustment, Calendar);
+
+impl Timeline for Adjust {
+	fn duration_hint(&self) -> Duration {
+		self.0.duration_hint()
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		let adjustment = self.1;
+		let calendar = self.2.clone();
+		// Adjusted days move past the days off around them at most
+		let margin = Duration::days(calendar.max_days_off() + 1);
+
+		move_within(
+			self.0.iter_within(widen(&interval, margin)),
+			&interval,
+			move |interval| {
+				let day = interval.from.date_naive();
+				let adjusted = calendar.adjust_date(day, adjustment);
+				if adjusted == day {
+					Some(vec![interval])
+				} else {
+					Some(move_to_day(interval, adjusted))
+				}
+			},
+		)
+	}
+}
+
+/// How far to move intervals, days and months are counted on the calendar
+/// so the wall clock time is kept
+#[derive(Debug, Clone, PartialEq)]
+pub enum Offset {
+	Duration(Duration),
+	Days(i64),
+	Months(i32),
+	BusinessDays(i64, Calendar),
+}
+
+impl Offset {
+	/// How far the offset can move an interval at most, if that fits in a
+	/// duration
+	pub(crate) fn margin(&self) -> Option<Duration> {
+		let days = match self {
+			Offset::Duration(duration) => return Some(duration.abs()),
+			Offset::Days(days) => days.checked_abs()?.checked_add(1)?,
+			Offset::Months(months) => 31 * i64::from(months.unsigned_abs()) + 1,
+			// Every business day can come after a row of days off
+			Offset::BusinessDays(days, calendar) => days
+				.checked_abs()?
+				.checked_mul(calendar.max_days_off() + 1)?
+				.checked_add(1)?,
+		};
+
+		Duration::try_days(days)
+	}
+
+	/// Move the interval, unless that takes it out of the range of dates
+	pub(crate) fn apply(&self, interval: Interval) -> Option<Vec<Interval>> {
+		let day = interval.from.date_naive();
+		let moved = match self {
+			Offset::Duration(by) => {
+				return Some(vec![Interval {
+					from: interval.from.checked_add_signed(*by)?,
+					to: match interval.to {
+						Some(to) => Some(to.checked_add_signed(*by)?),
+						None => None,
+					},
+				}]);
+			}
+			Offset::Days(days) => {
+				day.checked_add_signed(Duration::try_days(*days)?)?
+			}
+			Offset::Months(months) => {
+				// Days missing in the target month become its last day
+				let n = Months::new(months.unsigned_abs());
+				if *months < 0 {
+					day.checked_sub_months(n)?
+				} else {
+					day.checked_add_months(n)?
+				}
+			}
+			Offset::BusinessDays(days, calendar) => {
+				calendar.add_business_days(day, *days)
+			}
+		};
+
+		Some(move_to_day(interval, moved))
+	}
+}
+
+#[derive(Debug)]
+pub struct Shift(Box<dyn Timeline>, Offset);
+
+impl Timeline for Shift {
+	fn duration_hint(&self) -> Duration {
+		self.0.duration_hint()
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		let offset = self.1.clone();
+		let margin = match offset.margin() {
+			Some(margin) => margin,
+			// The offset moves every interval out of the range of dates
+			None => return Box::new(std::iter::empty()),
+		};
+
+		move_within(
+			self.0.iter_within(widen(&interval, margin)),
+			&interval,
+			move |interval| offset.apply(interval),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::at::At;
+	use crate::day::ShortMonth;
+	use crate::period::Period;
+	use crate::timeframe::TimeFrame;
+	use crate::weekday::Weekend;
+	use chrono::{NaiveTime, Weekday};
+	use insta::assert_debug_snapshot_matches;
+
+	#[test]
+	fn business_hours_shifted_except_friday() {
+		let hours = TimeFrame::new(
+			NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+			NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+		);
+
+		let timeline = Period::Weekday(Weekend::default())
+			.and(hours.shift(Duration::hours(1)))
+			.except(Weekday::Fri)
+			.clip(
+				Interval::from("2019-01-02T12:00:00Z".parse().unwrap())
+					.until("2019-01-07T12:00:00Z".parse().unwrap()),
+			);
+
+		assert_debug_snapshot_matches!(
+			"business_hours_shifted_except_friday",
+			timeline
+				.iter_within(Interval::from(
+					"2019-01-01T00:00:00Z".parse().unwrap()
+				))
+				.collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn on_the_25th_or_the_previous_business_day() {
+		let paydays: Vec<String> = Period::DayOfMonth(25, ShortMonth::Skip)
+			.adjust(Adjustment::Preceding, Calendar::us_federal())
+			.iter_within(Interval::from(
+				"2021-09-01T00:00:00Z".parse().unwrap(),
+			))
+			.take(4)
+			.map(|interval| interval.from.date_naive().to_string())
+			.collect();
+
+		assert_eq!(
+			paydays,
+			vec!["2021-09-24", "2021-10-25", "2021-11-24", "2021-12-23"]
+		);
+	}
+
+	#[test]
+	fn three_business_days_before_the_last_day() {
+		let days: Vec<String> = Period::DayOfMonth(-1, ShortMonth::Skip)
+			.offset(Offset::BusinessDays(-3, Calendar::new(vec![])))
+			.iter_within(Interval::from(
+				"2019-03-01T00:00:00Z".parse().unwrap(),
+			))
+			.take(3)
+			.map(|interval| interval.from.date_naive().to_string())
+			.collect();
+
+		// The 31st of march 2019 is a sunday
+		assert_eq!(days, vec!["2019-03-27", "2019-04-25", "2019-05-28"]);
+	}
+
+	#[test]
+	fn whole_days_moved_across_dst() {
+		let interval = Interval::from("2019-03-28T00:00:00Z".parse().unwrap())
+			.with_timezone(chrono_tz::Europe::Berlin);
+		let first = |timeline: &dyn Timeline| {
+			let day = timeline.iter_within(interval.clone()).next().unwrap();
+			let format = |at: DateTime<Zone>| at.format("%m-%d %H:%M %Z");
+			format!("{}..{}", format(day.from), format(day.to.unwrap()))
+		};
+
+		// Clocks go forward on sunday the 31st of march
+		let saturday = Period::DayOfWeek(Weekday::Sat);
+		assert_eq!(
+			first(&saturday.offset(Offset::Days(1))),
+			"03-31 00:00 CET..04-01 00:00 CEST"
+		);
+		let sunday = Period::DayOfWeek(Weekday::Sun);
+		assert_eq!(
+			first(&sunday.adjust(Adjustment::Preceding, Calendar::new(vec![]))),
+			"03-29 00:00 CET..03-30 00:00 CET"
+		);
+	}
+
+	#[test]
+	fn a_month_after_the_last_day() {
+		let days: Vec<String> = Period::DayOfMonth(-1, ShortMonth::Skip)
+			.offset(Offset::Months(1))
+			.iter_within(
+				Interval::from("2019-01-01T00:00:00Z".parse().unwrap())
+					.until("2019-05-01T00:00:00Z".parse().unwrap()),
+			)
+			.map(|interval| interval.from.date_naive().to_string())
+			.collect();
+
+		assert_eq!(
+			days,
+			vec!["2019-01-31", "2019-02-28", "2019-03-28", "2019-04-30"]
+		);
+	}
+
+	#[test]
+	fn touching_intervals_are_merged() {
+		let shifts = At::new(vec![
+			NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+			NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+		])
+		.lasting(Duration::hours(3));
+		let interval = Interval::from("2019-01-01T00:00:00Z".parse().unwrap())
+			.until("2019-01-02T00:00:00Z".parse().unwrap());
+
+		// The shifts only touch at noon, without overlapping
+		assert_eq!(shifts.iter_within(interval.clone()).count(), 2);
+
+		let merged: Vec<Interval> =
+			shifts.merge_adjacent().iter_within(interval).collect();
+		assert_eq!(merged.len(), 1);
+		assert_eq!(merged[0].to.unwrap() - merged[0].from, Duration::hours(6));
+	}
+
+	#[test]
+	fn overlapping_meetings_are_merged() {
+		let meetings = At::new(vec![
+			NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+			NaiveTime::from_hms_opt(10, 30, 0).unwrap(),
+		])
+		.lasting(Duration::hours(1))
+		.merge_adjacent();
+
+		let busy = meetings
+			.iter_within(Interval::from(
+				"2019-01-01T00:00:00Z".parse().unwrap(),
+			))
+			.next()
+			.unwrap();
+
+		assert_eq!(
+			busy.from.time(),
+			NaiveTime::from_hms_opt(10, 0, 0).unwrap()
+		);
+		assert_eq!(
+			busy.to.unwrap().time(),
+			NaiveTime::from_hms_opt(11, 30, 0).unwrap()
+		);
+	}
+}
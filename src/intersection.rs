@@ -0,0 +1,119 @@
+use chrono::Duration;
+use std::iter::Peekable;
+
+use crate::interval::{Interval, Timeline};
+use crate::merge::Merge;
+
+type Stream = Peekable<Merge<Box<dyn Iterator<Item = Interval>>>>;
+
+/// Time covered by both timelines
+#[derive(Debug)]
+pub struct Intersection(Box<dyn Timeline>, Box<dyn Timeline>);
+
+impl Intersection {
+	pub fn new(a: Box<dyn Timeline>, b: Box<dyn Timeline>) -> Self {
+		Intersection(a, b)
+	}
+}
+
+impl Timeline for Intersection {
+	fn duration_hint(&self) -> Duration {
+		self.0.duration_hint().min(self.1.duration_hint())
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		Box::new(IntersectionIterator {
+			a: Merge::new(self.0.iter_within(interval.clone()), usize::MAX)
+				.peekable(),
+			b: Merge::new(self.1.iter_within(interval), usize::MAX).peekable(),
+		})
+	}
+}
+
+struct IntersectionIterator {
+	a: Stream,
+	b: Stream,
+}
+
+impl Iterator for IntersectionIterator {
+	type Item = Interval;
+
+	fn next(&mut self) -> Option<Interval> {
+		loop {
+			let a = self.a.peek()?;
+			let b = self.b.peek()?;
+
+			if a.ends_before(b) {
+				self.a.next();
+				continue;
+			}
+
+			if b.ends_before(a) {
+				self.b.next();
+				continue;
+			}
+
+			let from = a.from.max(b.from);
+			let to = match (a.to, b.to) {
+				(Some(a), Some(b)) => Some(a.min(b)),
+				(a, b) => a.or(b),
+			};
+
+			// Whichever interval ends first can't overlap with anything else
+			match (a.to, b.to) {
+				(Some(a_to), Some(b_to)) if a_to < b_to => {
+					self.a.next();
+				}
+				(Some(a_to), Some(b_to)) if b_to < a_to => {
+					self.b.next();
+				}
+				(Some(_), None) => {
+					self.a.next();
+				}
+				(None, Some(_)) => {
+					self.b.next();
+				}
+				_ => {
+					self.a.next();
+					self.b.next();
+				}
+			}
+
+			return Some(Interval { from, to });
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::period::Period;
+	use crate::timeframe::TimeFrame;
+	use crate::weekday::Weekend;
+	use chrono::NaiveTime;
+	use insta::assert_debug_snapshot_matches;
+
+	#[test]
+	fn business_hours() {
+		let business_hours = Intersection::new(
+			Box::new(Period::Weekday(Weekend::default())),
+			Box::new(TimeFrame::new(
+				NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+				NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+			)),
+		);
+
+		assert_debug_snapshot_matches!(
+			"business_hours",
+			business_hours
+				.iter_within(Interval::from(
+					"2019-01-04T00:00:00Z".parse().unwrap()
+				))
+				.take(3)
+				.collect::<Vec<_>>()
+		);
+	}
+}
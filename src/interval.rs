@@ -1,22 +1,81 @@
 use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
 use std::fmt::Debug;
 
+use crate::types::Dimension;
+use crate::zone::Zone;
+
+/// A half-open `[from, to)` range of time in a particular time zone, day
+/// boundaries and wall clock times are resolved in the zone of `from`.
+///
+/// Adjacent intervals share the boundary instant, an interval with
+/// `from == to` denotes a single instant.
 #[derive(Debug, Clone)]
 pub struct Interval {
-	pub from: DateTime<Utc>,
-	pub to: Option<DateTime<Utc>>,
+	pub from: DateTime<Zone>,
+	pub to: Option<DateTime<Zone>>,
 }
 
 impl Interval {
 	pub fn from_now() -> Self {
+		Interval::from(Utc::now())
+	}
+
+	pub fn from(from: DateTime<Utc>) -> Self {
 		Interval {
-			from: Utc::now(),
+			from: from.with_timezone(&Zone::from(Tz::UTC)),
 			to: None,
 		}
 	}
 
-	pub fn from(from: DateTime<Utc>) -> Self {
-		Interval { from, to: None }
+	/// Limit the interval with the given end
+	pub fn until(self, to: DateTime<Utc>) -> Self {
+		Interval {
+			to: Some(to.with_timezone(&self.from.timezone())),
+			..self
+		}
+	}
+
+	/// Whether the interval denotes a single instant
+	pub fn is_instant(&self) -> bool {
+		self.to == Some(self.from)
+	}
+
+	/// Whether the interval is over before `other` starts
+	pub fn ends_before(&self, other: &Interval) -> bool {
+		match self.to {
+			None => false,
+			// An instant at the start of the other interval belongs to it
+			Some(to) if self.is_instant() => to < other.from,
+			Some(to) => to <= other.from,
+		}
+	}
+
+	/// The part of time covered by both intervals
+	pub fn intersect(&self, other: &Interval) -> Option<Interval> {
+		let from = self.from.max(other.from);
+		let to = match (self.to, other.to) {
+			(Some(a), Some(b)) => Some(a.min(b)),
+			(a, b) => a.or(b),
+		};
+
+		// Instants at the exclusive end of an interval don't belong to it
+		let ends_at = |i: &Interval| !i.is_instant() && i.to == Some(from);
+
+		match to {
+			Some(to) if to < from => None,
+			Some(to) if to == from && (ends_at(self) || ends_at(other)) => None,
+			_ => Some(Interval { from, to }),
+		}
+	}
+
+	/// Convert the interval to the given time zone
+	pub fn with_timezone(&self, zone: impl Into<Zone>) -> Self {
+		let zone = zone.into();
+		Interval {
+			from: self.from.with_timezone(&zone),
+			to: self.to.map(|to| to.with_timezone(&zone)),
+		}
 	}
 }
 
@@ -30,4 +89,41 @@ pub trait Timeline: Debug {
 		&self,
 		interval: Interval,
 	) -> Box<dyn Iterator<Item = Interval>>;
+
+	/// The calendar unit the timeline repeats within, like a day for times
+	/// of the day or a month for days of the month
+	fn cycle(&self) -> Option<Dimension> {
+		None
+	}
+
+	/// The start and the unit of the step an interval belongs to, for
+	/// timelines that step through several units at once like every 2
+	/// weeks
+	fn step(
+		&self,
+		_interval: &Interval,
+	) -> Option<(DateTime<Zone>, Dimension)> {
+		None
+	}
+}
+
+impl<T: Timeline + ?Sized> Timeline for Box<T> {
+	fn duration_hint(&self) -> Duration {
+		(**self).duration_hint()
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		(**self).iter_within(interval)
+	}
+
+	fn cycle(&self) -> Option<Dimension> {
+		(**self).cycle()
+	}
+
+	fn step(&self, interval: &Interval) -> Option<(DateTime<Zone>, Dimension)> {
+		(**self).step(interval)
+	}
 }
@@ -1,7 +1,36 @@
+//! An experimental library to work with recurring time intervals.
+//!
+//! Schedules are usually built from a human readable description:
+//!
+//! ```
+//! let schedule = later::parse("every sat, sun at 3:00am").unwrap();
+//! ```
+//!
+//! Occurrences last for a while with "lasting", while "for" limits the
+//! whole schedule, by a number of occurrences or a span of time:
+//!
+//! ```
+//! let meetings = later::parse("every monday at 10am lasting 1 hour").unwrap();
+//! let week = later::parse("every 30 minutes for 1 week").unwrap();
+//! ```
+//!
+//! Right after a time, "for" is how long the occurrences last, so "every
+//! monday at 10am for 1 hour" is the same as the first schedule above.
+//!
+//! or composed by hand from the [`Timeline`] implementors re-exported here.
+
 mod at;
+mod calendar;
+mod clock;
 mod combine;
+mod complement;
+mod date;
+mod day;
+mod difference;
 mod duration;
 mod every;
+mod ext;
+mod intersection;
 mod interval;
 mod merge;
 mod merge_n;
@@ -12,5 +41,48 @@ mod schedule;
 mod time;
 mod timeframe;
 mod types;
+mod union;
 mod utils;
+mod week;
 mod weekday;
+mod zone;
+
+pub use chrono_tz::Tz;
+
+pub use crate::at::At;
+pub use crate::calendar::{
+	Adjustment, Calendar, Holiday, HolidayRule, Observance,
+};
+pub use crate::clock::{MinuteOfHour, SecondOfMinute};
+pub use crate::complement::Complement;
+pub use crate::date::{DateSpec, Dates, Moment};
+pub use crate::day::ShortMonth;
+pub use crate::difference::Difference;
+pub use crate::duration::Anchor;
+pub use crate::every::Every;
+pub use crate::ext::{
+	Adjust, Clip, Lasting, MergeAdjacent, Nth, Offset, Shift, TimelineExt,
+};
+pub use crate::intersection::Intersection;
+pub use crate::interval::{Interval, Timeline};
+pub use crate::month::Align;
+pub use crate::parser::{
+	parse, parse_in, parse_relative_to, Error, ParseError,
+};
+pub use crate::period::Period;
+pub use crate::schedule::{Schedule, ScheduleIterator, Span};
+pub use crate::timeframe::{TimeFrame, WeekFrame};
+pub use crate::union::Union;
+pub use crate::utils::Of;
+pub use crate::week::Parity;
+pub use crate::weekday::Weekend;
+pub use crate::zone::{Dst, Gap, Overlap, Zone, ZoneOffset};
+
+/// Re-exports of the types needed for the most common use cases.
+pub mod prelude {
+	pub use crate::ext::TimelineExt;
+	pub use crate::interval::{Interval, Timeline};
+	pub use crate::month::Align;
+	pub use crate::parser::parse;
+	pub use crate::schedule::Schedule;
+}
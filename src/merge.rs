@@ -1,5 +1,4 @@
 use crate::interval::Interval;
-use chrono::Duration;
 
 pub use crate::merge_n::MergeN;
 
@@ -8,18 +7,16 @@ pub use crate::merge_n::MergeN;
 pub struct Merge<T: Iterator<Item = Interval>> {
 	prev: Option<Interval>,
 	base: T,
-	diff: Duration,
 	completed: bool,
 	max_merges: usize,
 	merges: usize,
 }
 
 impl<T: Iterator<Item = Interval>> Merge<T> {
-	pub fn new(base: T, diff: Duration, max_merges: usize) -> Self {
+	pub fn new(base: T, max_merges: usize) -> Self {
 		Merge {
 			base,
 			prev: None,
-			diff,
 			completed: false,
 			max_merges,
 			merges: 0,
@@ -46,7 +43,7 @@ impl<T: Iterator<Item = Interval>> Iterator for Merge<T> {
 			let value = self.base.next();
 			if value.is_none() {
 				self.completed = true;
-				return std::mem::replace(&mut self.prev, None);
+				return self.prev.take();
 			}
 
 			let value = value.unwrap();
@@ -62,14 +59,22 @@ impl<T: Iterator<Item = Interval>> Iterator for Merge<T> {
 					}
 				}
 				Some(prev) => {
-					let to = prev.to.as_ref().unwrap();
-					if value.from < *to || value.from - *to <= self.diff {
-						// merge intervals
-						prev.to = value.to;
+					let to = prev.to.unwrap();
+					if value.from <= to {
+						// merge overlapping and adjacent intervals
 						self.merges += 1;
+						match value.to {
+							Some(value_to) => prev.to = Some(value_to.max(to)),
+							None => {
+								// nothing can follow an interval without an end
+								self.completed = true;
+								prev.to = None;
+								return self.prev.take();
+							}
+						}
 						continue;
 					} else {
-						return std::mem::replace(&mut self.prev, Some(value));
+						return self.prev.replace(value);
 					}
 				}
 			}
@@ -96,7 +101,7 @@ mod tests {
 			)),
 		]);
 
-		let stream = Merge::new(stream, Duration::seconds(1), 100);
+		let stream = Merge::new(stream, 100);
 		let what: Vec<Interval> = stream.take(10).collect();
 		assert_debug_snapshot_matches!("merge_weekend", what);
 	}
@@ -1,5 +1,3 @@
-use chrono::Duration;
-
 use crate::interval::Interval;
 use crate::merge::Merge;
 
@@ -36,8 +34,7 @@ impl<T: Iterator<Item = Interval>> Iterator for MergeN<T> {
 			return None;
 		}
 
-		let mut merge =
-			Merge::new(items.into_iter(), Duration::seconds(1), self.how_many);
+		let mut merge = Merge::new(items.into_iter(), self.how_many);
 
 		merge.next()
 	}
@@ -1,57 +1,268 @@
 use crate::interval::Interval;
-use crate::utils::days_in_month;
-use chrono::{Datelike, TimeZone, Timelike};
+use crate::zone::Zone;
+use chrono::{
+	DateTime, Datelike, Month, Months, NaiveDate, NaiveDateTime, NaiveTime,
+};
+
+/// How quarters and years are aligned
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+	/// Start at calendar boundaries of a year beginning with the given
+	/// month, so `Calendar(Month::April)` makes Q1 span April to June
+	Calendar(Month),
+	/// Start at the instant the iteration starts
+	Rolling,
+}
+
+impl Default for Align {
+	fn default() -> Self {
+		Align::Calendar(Month::January)
+	}
+}
 
 pub struct MonthIterator {
 	interval: Interval,
-	start_day: u32,
+	start: NaiveDateTime,
+	index: u32,
 }
 
 impl MonthIterator {
 	pub fn new(interval: Interval) -> MonthIterator {
-		let start_day = interval.from.day();
 		MonthIterator {
+			start: interval.from.naive_local(),
 			interval,
-			start_day,
+			index: 0,
 		}
 	}
+
+	/// The wall clock time the given number of months after the start, days
+	/// missing in shorter months become their last day
+	fn boundary(&self, months: u32) -> Option<NaiveDateTime> {
+		self.start.checked_add_months(Months::new(months))
+	}
 }
 
 impl Iterator for MonthIterator {
 	type Item = Interval;
 	fn next(&mut self) -> Option<Interval> {
-		let from = self.interval.from.clone();
-		let month = from.month();
+		let zone = self.interval.from.timezone();
+		loop {
+			let to = zone.localize(&self.boundary(self.index + 1)?);
+			if self.interval.to.is_some()
+				&& to > *self.interval.to.as_ref().unwrap()
+			{
+				return None;
+			}
+
+			// Later months start at the same wall clock time, unless the
+			// policy skips it
+			let from = match self.index {
+				0 => Some(self.interval.from),
+				index => zone.period_start(&self.boundary(index)?),
+			};
+
+			self.index += 1;
+			if let Some(from) = from {
+				return Some(Interval { from, to: Some(to) });
+			}
+		}
+	}
+}
 
-		let next_year = if month == 12 {
-			from.year() + 1
+/// An iterator over a range of months repeated every year, the range wraps
+/// around the end of the year when `to` comes before `from`.
+pub struct MonthRangeIterator {
+	from: u32,
+	to: u32,
+	interval: Interval,
+	completed: bool,
+}
+
+impl MonthRangeIterator {
+	pub fn new(from: Month, to: Month, interval: Interval) -> Self {
+		MonthRangeIterator {
+			from: from.number_from_month(),
+			to: to.number_from_month(),
+			interval,
+			completed: false,
+		}
+	}
+
+	fn contains(&self, month: u32) -> bool {
+		if self.from <= self.to {
+			self.from <= month && month <= self.to
 		} else {
-			from.year()
-		};
+			month >= self.from || month <= self.to
+		}
+	}
+}
 
-		let next_month = if month == 12 { 1 } else { month + 1 };
+impl Iterator for MonthRangeIterator {
+	type Item = Interval;
 
-		let days_in_next_month = days_in_month(next_year, next_month);
-		let to = from;
-		let next_day = if days_in_next_month < self.start_day {
-			days_in_next_month
+	fn next(&mut self) -> Option<Interval> {
+		if self.completed {
+			return None;
+		}
+
+		let from = self.interval.from;
+		let zone = from.timezone();
+		let date = from.date_naive();
+		let month = date.month();
+
+		let year = if self.contains(month) {
+			if self.from <= self.to || month >= self.from {
+				date.year()
+			} else {
+				date.year() - 1
+			}
+		} else if month < self.from {
+			date.year()
+		} else {
+			date.year() + 1
+		};
+
+		let end_year = if self.to < self.from { year + 1 } else { year };
+		let (end_year, end_month) = if self.to == 12 {
+			(end_year + 1, 1)
 		} else {
-			self.start_day
+			(end_year, self.to + 1)
+		};
+
+		let first_day = |year, month| {
+			NaiveDate::from_ymd_opt(year, month, 1)
+				.unwrap()
+				.and_time(NaiveTime::MIN)
+		};
+
+		let end = zone.localize(&first_day(end_year, end_month));
+		let start = match zone.period_start(&first_day(year, self.from)) {
+			Some(start) => start.max(from),
+			// The range is skipped this year
+			None => {
+				self.interval.from = end;
+				return self.next();
+			}
 		};
 
-		let to = to
-			.offset()
-			.ymd(next_year, next_month, next_day)
-			.and_hms_nano(to.hour(), to.minute(), to.second(), to.nanosecond());
+		if self.interval.to.is_some() && start >= self.interval.to.unwrap() {
+			self.completed = true;
+			return None;
+		}
+
+		if self.interval.to.is_some() && self.interval.to.unwrap() < end {
+			self.completed = true;
+			return Some(Interval {
+				from: start,
+				to: self.interval.to,
+			});
+		}
+
+		self.interval.from = end;
+
+		Some(Interval {
+			from: start,
+			to: Some(end),
+		})
+	}
+}
+
+/// An iterator over consecutive runs of months aligned to calendar
+/// boundaries, like quarters of a (fiscal) year.
+pub struct AlignedMonthsIterator {
+	months: i32,
+	start: u32,
+	interval: Interval,
+	completed: bool,
+}
 
-		if self.interval.to.is_some()
-			&& to > *self.interval.to.as_ref().unwrap()
-		{
+impl AlignedMonthsIterator {
+	pub fn new(months: i32, start: Month, interval: Interval) -> Self {
+		AlignedMonthsIterator {
+			completed: months <= 0,
+			months,
+			start: start.number_from_month(),
+			interval,
+		}
+	}
+}
+
+/// The first day of the month counted from the start of the first year,
+/// unless that's past the range of dates
+fn first_day(start: u32, index: i32) -> Option<NaiveDate> {
+	let month = index.checked_add(start as i32 - 1)?;
+	NaiveDate::from_ymd_opt(
+		month.div_euclid(12),
+		month.rem_euclid(12) as u32 + 1,
+		1,
+	)
+}
+
+/// The index of the first month of the run the date belongs to
+fn run_index(months: i32, start: u32, date: NaiveDate) -> i32 {
+	let index = date.year() * 12 + date.month() as i32 - start as i32;
+	index.div_euclid(months) * months
+}
+
+/// The start of the run of months an instant belongs to, runs are aligned
+/// to years beginning with the given month
+pub fn run_start(
+	months: i32,
+	start: Month,
+	at: &DateTime<Zone>,
+) -> Option<DateTime<Zone>> {
+	let start = start.number_from_month();
+	let index = run_index(months, start, at.date_naive());
+	let day = first_day(start, index)?;
+	Some(at.timezone().localize(&day.and_time(NaiveTime::MIN)))
+}
+
+impl Iterator for AlignedMonthsIterator {
+	type Item = Interval;
+
+	fn next(&mut self) -> Option<Interval> {
+		if self.completed {
 			return None;
 		}
 
-		self.interval.from = to;
+		let from = self.interval.from;
+		let zone = from.timezone();
+		let date = from.date_naive();
+
+		let index = run_index(self.months, self.start, date);
+
+		let midnight = |day: NaiveDate| day.and_time(NaiveTime::MIN);
+		// Runs ending past the range of dates end the iteration
+		let end = first_day(self.start, index.checked_add(self.months)?)?;
+		let end = zone.localize(&midnight(end));
+		let start = first_day(self.start, index)?;
+		let start = match zone.period_start(&midnight(start)) {
+			Some(start) => start.max(from),
+			// The run of months is skipped
+			None => {
+				self.interval.from = end;
+				return self.next();
+			}
+		};
+
+		if self.interval.to.is_some() && start >= self.interval.to.unwrap() {
+			self.completed = true;
+			return None;
+		}
+
+		if self.interval.to.is_some() && self.interval.to.unwrap() < end {
+			self.completed = true;
+			return Some(Interval {
+				from: start,
+				to: self.interval.to,
+			});
+		}
+
+		self.interval.from = end;
 
-		Some(Interval { from, to: Some(to) })
+		Some(Interval {
+			from: start,
+			to: Some(end),
+		})
 	}
 }
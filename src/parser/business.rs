@@ -0,0 +1,100 @@
+use chrono_tz::Tz;
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::space1,
+	combinator::{map, opt},
+	sequence::{terminated, tuple},
+};
+
+use crate::calendar::{Adjustment, Calendar};
+use crate::ext::TimelineExt;
+use crate::interval::Timeline;
+
+use super::date::{parse_country, public_holidays};
+use super::day::parse_on;
+use super::error::{ParseError, ParseResult};
+
+/// Parse "business days" or "us business days" into the calendar they are
+/// counted on, which is the one of the time zone when no country is given
+pub fn parse_business_days(input: &str, tz: Tz) -> ParseResult<'_, Calendar> {
+	let (rest, country) = opt(terminated(parse_country, space1))(input)?;
+	let (rest, _) = alt((tag("business days"), tag("business day")))(rest)?;
+
+	match country.or_else(|| public_holidays(tz)) {
+		Some(calendar) => Ok((rest, calendar)),
+		None => Err(ParseError::Unsupported.into_fail(input)),
+	}
+}
+
+/// Parse ", or the previous business day", ", or the next business day in
+/// the same month" and similar
+fn parse_adjustment(
+	input: &str,
+	tz: Tz,
+) -> ParseResult<'_, (Adjustment, Calendar)> {
+	let (input, _) =
+		tuple((opt(tag(",")), space1, tag("or"), space1, tag("the")))(input)?;
+	let (input, _) = space1(input)?;
+	let (input, direction) =
+		alt((tag("next"), tag("previous"), tag("nearest")))(input)?;
+	let (input, _) = space1(input)?;
+	let (input, calendar) = parse_business_days(input, tz)?;
+	let (input, adjustment) = match direction {
+		"next" => map(opt(tag(" in the same month")), |same| {
+			if same.is_some() {
+				Adjustment::ModifiedFollowing
+			} else {
+				Adjustment::Following
+			}
+		})(input)?,
+		"previous" => (input, Adjustment::Preceding),
+		_ => (input, Adjustment::Nearest),
+	};
+
+	Ok((input, (adjustment, calendar)))
+}
+
+/// Parse "on the 25th, or the previous business day"
+pub fn parse_on_adjusted(
+	input: &str,
+	tz: Tz,
+) -> ParseResult<'_, Box<dyn Timeline>> {
+	let (input, on) = parse_on(input)?;
+	let (input, (adjustment, calendar)) = parse_adjustment(input, tz)?;
+	Ok((input, Box::new(on.adjust(adjustment, calendar))))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn adjustments() {
+		let tz = Tz::America__New_York;
+		assert_eq!(
+			parse_adjustment(", or the previous business day", tz).unwrap(),
+			("", (Adjustment::Preceding, Calendar::us_federal()))
+		);
+		assert_eq!(
+			parse_adjustment(
+				" or the next uk business day in the same month",
+				Tz::UTC
+			)
+			.unwrap(),
+			(
+				"",
+				(Adjustment::ModifiedFollowing, Calendar::england_and_wales())
+			)
+		);
+	}
+
+	#[test]
+	fn business_days_need_a_known_country() {
+		assert!(parse_business_days("business days", Tz::UTC).is_err());
+		assert_eq!(
+			parse_business_days("german business day", Tz::UTC).unwrap(),
+			("", Calendar::germany())
+		);
+	}
+}
@@ -0,0 +1,188 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use chrono_tz::Tz;
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::{digit1, space1},
+	combinator::{map, opt},
+	sequence::{preceded, tuple},
+};
+
+use crate::calendar::Calendar;
+use crate::date::DateSpec;
+
+use super::error::{ParseError, ParseResult};
+use super::month::parse_month;
+use super::time::parse_time;
+use super::utils::parse_chain;
+
+pub fn parse_date(input: &str) -> ParseResult<'_, NaiveDate> {
+	let (input, (y, _, m, _, d)) =
+		tuple((digit1, tag("-"), digit1, tag("-"), digit1))(input)?;
+
+	let invalid = |e| ParseError::InvalidNumericValue(e).into_fail(input);
+	let y: i32 = y.parse().map_err(invalid)?;
+	let m: u32 = m.parse().map_err(invalid)?;
+	let d: u32 = d.parse().map_err(invalid)?;
+
+	let date = NaiveDate::from_ymd_opt(y, m, d)
+		.ok_or_else(|| ParseError::InvalidDate.into_fail(input))?;
+
+	Ok((input, date))
+}
+
+pub fn parse_datetime(input: &str) -> ParseResult<'_, NaiveDateTime> {
+	let (input, date) = parse_date(input)?;
+	let (input, time) =
+		opt(preceded(alt((tag("T"), space1)), parse_time))(input)?;
+
+	Ok((input, date.and_time(time.unwrap_or(chrono::NaiveTime::MIN))))
+}
+
+/// Parse "dec 25" into the same day every year
+fn parse_annual(input: &str) -> ParseResult<'_, DateSpec> {
+	let (input, month) = parse_month(input)?;
+	let (input, _) = space1(input)?;
+	let (input, day) = digit1(input)?;
+	let day: u32 = day
+		.parse()
+		.map_err(|e| ParseError::InvalidNumericValue(e).into_fail(input))?;
+
+	// Checked against a leap year, so february 29th is accepted
+	if NaiveDate::from_ymd_opt(2000, month.number_from_month(), day).is_none() {
+		return Err(ParseError::InvalidDate.into_fail(input));
+	}
+
+	Ok((input, DateSpec::Annual(month, day)))
+}
+
+/// The holidays observed in the country a time zone belongs to
+pub fn public_holidays(tz: Tz) -> Option<Calendar> {
+	match tz {
+		Tz::America__New_York
+		| Tz::America__Chicago
+		| Tz::America__Denver
+		| Tz::America__Phoenix
+		| Tz::America__Los_Angeles
+		| Tz::America__Anchorage
+		| Tz::Pacific__Honolulu => Some(Calendar::us_federal()),
+		Tz::Europe__London => Some(Calendar::england_and_wales()),
+		Tz::Europe__Berlin => Some(Calendar::germany()),
+		_ => None,
+	}
+}
+
+/// Parse "us", "uk" or "german" into the holidays of the country
+pub fn parse_country(input: &str) -> ParseResult<'_, Calendar> {
+	alt((
+		map(tag("us"), |_| Calendar::us_federal()),
+		map(tag("uk"), |_| Calendar::england_and_wales()),
+		map(tag("german"), |_| Calendar::germany()),
+	))(input)
+}
+
+/// Parse "us holidays", "uk holidays", "german holidays" or "public
+/// holidays" in the country of the time zone
+fn parse_holidays(input: &str, tz: Tz) -> ParseResult<'_, Calendar> {
+	let (rest, calendar) = alt((
+		map(parse_country, Some),
+		map(tag("public"), |_| public_holidays(tz)),
+	))(input)?;
+	let (rest, _) = space1(rest)?;
+	let (rest, _) = tag("holidays")(rest)?;
+
+	match calendar {
+		Some(calendar) => Ok((rest, calendar)),
+		None => Err(ParseError::Unsupported.into_fail(input)),
+	}
+}
+
+/// Parse "2019-12-23", "2019-12-23T10:00", "dec 25" or "us holidays"
+fn parse_date_spec(input: &str, tz: Tz) -> ParseResult<'_, DateSpec> {
+	match parse_holidays(input, tz) {
+		Ok((input, calendar)) => {
+			return Ok((input, DateSpec::Holidays(calendar)))
+		}
+		Err(nom::Err::Error(_)) => {}
+		Err(e) => return Err(e),
+	}
+
+	if let Ok((input, date)) = parse_date(input) {
+		let (input, time) =
+			opt(preceded(alt((tag("T"), space1)), parse_time))(input)?;
+		let spec = match time {
+			Some(time) => DateSpec::DateTime(date.and_time(time)),
+			None => DateSpec::Date(date),
+		};
+		return Ok((input, spec));
+	}
+
+	parse_annual(input)
+}
+
+fn parse_dates_after<'a>(
+	input: &'a str,
+	keyword: &'static str,
+	tz: Tz,
+) -> ParseResult<'a, Vec<DateSpec>> {
+	let (input, _) = tag(keyword)(input)?;
+	let (input, _) = space1(input)?;
+	let (input, _) = opt(tuple((tag("on"), space1)))(input)?;
+	parse_chain(input, |input| parse_date_spec(input, tz))
+}
+
+/// Parse "except on dec 25 and jan 1", "except 2019-12-23" or "except
+/// public holidays"
+pub fn parse_except(input: &str, tz: Tz) -> ParseResult<'_, Vec<DateSpec>> {
+	parse_dates_after(input, "except", tz)
+}
+
+/// Parse "also on 2019-12-28" or "also 2019-12-28T10:00"
+pub fn parse_also(input: &str, tz: Tz) -> ParseResult<'_, Vec<DateSpec>> {
+	parse_dates_after(input, "also", tz)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_date_time() {
+		assert_eq!(
+			parse_datetime("2020-10-30T20:00").unwrap().1,
+			NaiveDate::from_ymd_opt(2020, 10, 30)
+				.unwrap()
+				.and_hms_opt(20, 0, 0)
+				.unwrap()
+		)
+	}
+
+	#[test]
+	fn parse_invalid_date() {
+		assert!(parse_date("2010-30-10").is_err())
+	}
+
+	#[test]
+	fn except_on_dates() {
+		use chrono::Month;
+
+		let date = NaiveDate::from_ymd_opt(2019, 12, 23).unwrap();
+		assert_eq!(
+			parse_except(
+				"except on dec 25, jan 1 and 2019-12-23T10:00",
+				Tz::UTC
+			)
+			.unwrap(),
+			(
+				"",
+				vec![
+					DateSpec::Annual(Month::December, 25),
+					DateSpec::Annual(Month::January, 1),
+					DateSpec::DateTime(date.and_hms_opt(10, 0, 0).unwrap()),
+				]
+			)
+		);
+		assert!(parse_except("except feb 30", Tz::UTC).is_err());
+		assert!(parse_except("except public holidays", Tz::UTC).is_err());
+	}
+}
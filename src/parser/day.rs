@@ -0,0 +1,129 @@
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::{digit1, space1},
+	combinator::{map, opt},
+	sequence::{preceded, tuple},
+};
+
+use crate::day::ShortMonth;
+use crate::every::Every;
+use crate::period::Period;
+
+use super::error::{ParseError, ParseResult};
+use super::utils::parse_chain;
+
+fn parse_the(input: &str) -> ParseResult<'_, ()> {
+	map(opt(tuple((tag("the"), space1))), |_| ())(input)
+}
+
+/// Parse "15th" and similar
+fn parse_nth(input: &str) -> ParseResult<'_, i32> {
+	let (input, digits) = digit1(input)?;
+	let (input, _) = alt((tag("st"), tag("nd"), tag("rd"), tag("th")))(input)?;
+	let nth = digits
+		.parse()
+		.map_err(|e| ParseError::InvalidNumericValue(e).into_fail(input))?;
+
+	Ok((input, nth))
+}
+
+fn parse_nth_to_last(input: &str) -> ParseResult<'_, i32> {
+	let (input, nth) = parse_nth(input)?;
+	let (input, _) = space1(input)?;
+	let (input, _) = opt(tuple((tag("to"), space1)))(input)?;
+	let (input, _) = tag("last day")(input)?;
+	Ok((input, -nth))
+}
+
+/// Parse a day of the month, days counted from the end are negative
+fn parse_day_of_month(input: &str) -> ParseResult<'_, i32> {
+	let (input, _) = parse_the(input)?;
+	let (input, nth) =
+		alt((map(tag("last day"), |_| -1), parse_nth_to_last, parse_nth))(
+			input,
+		)?;
+
+	if nth == 0 || nth.abs() > 31 {
+		return Err(ParseError::InvalidDate.into_fail(input));
+	}
+
+	let (input, _) = opt(tuple((space1, tag("day"))))(input)?;
+	Ok((input, nth))
+}
+
+/// Parse "on the 1st and 15th", "on the last day of the month", "on the
+/// 31st or the last day" or "on the 31st, or roll to next month"
+pub fn parse_on(input: &str) -> ParseResult<'_, Every> {
+	let (input, _) = tag("on")(input)?;
+	let (input, _) = space1(input)?;
+	parse_days_of_month(input)
+}
+
+/// Parse "the 1st and 15th" or "the last day of the month"
+pub fn parse_days_of_month(input: &str) -> ParseResult<'_, Every> {
+	let (input, days) = parse_chain(input, parse_day_of_month)?;
+	let (input, _) =
+		opt(tuple((space1, tag("of"), space1, parse_the, tag("month"))))(
+			input,
+		)?;
+	let (input, short) = opt(preceded(
+		tuple((opt(tag(",")), space1, tag("or"), space1)),
+		alt((
+			map(tuple((parse_the, tag("last day"))), |_| ShortMonth::Clamp),
+			map(tag("roll to next month"), |_| ShortMonth::Roll),
+			map(tag("roll to the next month"), |_| ShortMonth::Roll),
+		)),
+	))(input)?;
+	let short = short.unwrap_or(ShortMonth::Skip);
+
+	let periods = days
+		.into_iter()
+		.map(|day| Period::DayOfMonth(day, short))
+		.collect();
+
+	Ok((input, Every::new(periods)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn on(input: &str) -> Every {
+		let (rest, every) = parse_on(input).unwrap();
+		assert_eq!(rest, "");
+		every
+	}
+
+	#[test]
+	fn parse_on_days() {
+		assert_eq!(
+			on("on the 1st and 15th"),
+			Every::new(vec![
+				Period::DayOfMonth(1, ShortMonth::Skip),
+				Period::DayOfMonth(15, ShortMonth::Skip)
+			])
+		);
+		assert_eq!(
+			on("on the last day of the month"),
+			Every::new(vec![Period::DayOfMonth(-1, ShortMonth::Skip)])
+		);
+		assert_eq!(
+			on("on the 2nd to last day"),
+			Every::new(vec![Period::DayOfMonth(-2, ShortMonth::Skip)])
+		);
+		assert_eq!(
+			on("on the 31st or the last day"),
+			Every::new(vec![Period::DayOfMonth(31, ShortMonth::Clamp)])
+		);
+		assert_eq!(
+			on("on the 31st, or roll to next month"),
+			Every::new(vec![Period::DayOfMonth(31, ShortMonth::Roll)])
+		);
+	}
+
+	#[test]
+	fn parse_on_invalid_day() {
+		assert!(parse_on("on the 32nd").is_err());
+	}
+}
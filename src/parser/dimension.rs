@@ -3,7 +3,7 @@ use nom::character::complete::alpha1;
 use super::error::{ParseError, ParseResult};
 use crate::types::Dimension;
 
-pub fn parse_dimension(input: &str) -> ParseResult<Dimension> {
+pub fn parse_dimension(input: &str) -> ParseResult<'_, Dimension> {
 	let (input, dim) = alpha1(input)?;
 	match dim {
 		"s" | "sec" | "secs" | "second" | "seconds" => {
@@ -16,8 +16,8 @@ pub fn parse_dimension(input: &str) -> ParseResult<Dimension> {
 		"d" | "ds" | "day" | "days" => Ok((input, Dimension::Day)),
 		"w" | "wk" | "wks" | "week" | "weeks" => Ok((input, Dimension::Week)),
 		"mm" | "month" | "months" => Ok((input, Dimension::Month)),
-		"q" | "quarter" | "quarters" | "y" => Ok((input, Dimension::Year)),
-		"yr" | "yrs" | "years" => Ok((input, Dimension::Year)),
+		"q" | "quarter" | "quarters" => Ok((input, Dimension::Quarter)),
+		"y" | "yr" | "yrs" | "year" | "years" => Ok((input, Dimension::Year)),
 		_ => Err(ParseError::UnknownDimension {}.into_err(input)),
 	}
 }
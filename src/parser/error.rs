@@ -1,6 +1,9 @@
+use std::fmt;
+
 pub use nom::error::{ErrorKind, ParseError as ErrorExt};
 pub use nom::Err;
 
+/// The reason a schedule description could not be parsed.
 #[derive(Debug)]
 pub enum ParseError {
 	Layout(ErrorKind),
@@ -8,18 +11,38 @@ pub enum ParseError {
 	Unsupported,
 	InvalidNumericValue(std::num::ParseIntError),
 	InvalidTime(chrono::format::ParseError),
+	InvalidDate,
+	OutOfRange,
 }
 
 impl ParseError {
-	pub fn into_err(self, input: &str) -> Err<ErrorContext> {
+	pub fn into_err(self, input: &str) -> Err<ErrorContext<'_>> {
 		Err::Error(ErrorContext { input, error: self })
 	}
 
-	pub fn into_fail(self, input: &str) -> Err<ErrorContext> {
+	pub fn into_fail(self, input: &str) -> Err<ErrorContext<'_>> {
 		Err::Failure(ErrorContext { input, error: self })
 	}
 }
 
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ParseError::Layout(kind) => {
+				write!(f, "unexpected input ({})", kind.description())
+			}
+			ParseError::UnknownDimension => write!(f, "unknown dimension"),
+			ParseError::Unsupported => write!(f, "unsupported expression"),
+			ParseError::InvalidNumericValue(e) => {
+				write!(f, "invalid numeric value: {}", e)
+			}
+			ParseError::InvalidTime(e) => write!(f, "invalid time: {}", e),
+			ParseError::InvalidDate => write!(f, "invalid date"),
+			ParseError::OutOfRange => write!(f, "value out of range"),
+		}
+	}
+}
+
 #[derive(Debug)]
 pub struct ErrorContext<'a> {
 	input: &'a str,
@@ -40,4 +63,37 @@ impl<'a> ErrorExt<&'a str> for ErrorContext<'a> {
 }
 
 pub type ParseResult<'a, O> = Result<(&'a str, O), Err<ErrorContext<'a>>>;
-pub type SimpleResult<'a, O> = Result<(&'a str, O), Err<(&'a str, ErrorKind)>>;
+
+/// An error returned by [`parse`](crate::parse).
+#[derive(Debug)]
+pub struct Error {
+	kind: ParseError,
+	offset: usize,
+}
+
+impl Error {
+	pub(crate) fn new(source: &str, context: ErrorContext) -> Self {
+		Error {
+			kind: context.error,
+			offset: source.len() - context.input.len(),
+		}
+	}
+
+	/// The reason parsing failed
+	pub fn kind(&self) -> &ParseError {
+		&self.kind
+	}
+
+	/// Byte offset in the source where parsing failed
+	pub fn offset(&self) -> usize {
+		self.offset
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} at offset {}", self.kind, self.offset)
+	}
+}
+
+impl std::error::Error for Error {}
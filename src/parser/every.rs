@@ -1,81 +1,205 @@
-use chrono::Duration;
+use std::convert::TryFrom;
+
+use chrono::{Datelike, Duration, NaiveDate};
 use nom::{
 	bytes::complete::tag,
-	character::complete::{digit1, space0, space1},
+	character::complete::{alpha1, digit1, space0, space1},
+	combinator::{map, opt},
+	sequence::tuple,
 };
 
+use crate::day::max_nth;
+use crate::duration::Anchor;
 use crate::every::Every;
+use crate::month::Align;
 use crate::period::Period;
-use crate::types::{Dimension};
+use crate::types::Dimension;
+use crate::utils::Of;
 
 use super::dimension::parse_dimension;
 use super::error::{ParseError, ParseResult};
+use super::month::parse_month;
 use super::ordinal::parse_ordinal;
 use super::utils::parse_chain;
+use super::week::parse_week;
 use super::weekday::parse_weekday;
 
-pub fn parse_numeric_period(input: &str) -> ParseResult<Period> {
-	let (input, num) = digit1(input)?;
-	let (input, _) = space0(input)?;
+pub fn parse_numeric_period(input: &str) -> ParseResult<'_, Period> {
+	let (input, rolling) = opt(tuple((tag("rolling"), space1)))(input)?;
+	let (input, num) = opt(tuple((digit1, space0)))(input)?;
 	let (input, dim) = parse_dimension(input)?;
-	let num: i64 = num
-		.parse()
-		.map_err(|e| ParseError::InvalidNumericValue(e).into_fail(input))?;
+	let num: i64 = match num {
+		Some((num, _)) => num
+			.parse()
+			.map_err(|e| ParseError::InvalidNumericValue(e).into_fail(input))?,
+		None => 1,
+	};
+
+	// Quarters and years follow the calendar unless asked otherwise
+	let align = match rolling {
+		Some(_) => Align::Rolling,
+		None => Align::default(),
+	};
+
+	// Periods have to move forward and fit in a duration. Runs of months
+	// are aligned from year 0, so they have to fit twice in the range of
+	// dates to repeat at all.
+	let out_of_range = || ParseError::OutOfRange.into_fail(input);
+	if num == 0 {
+		return Err(out_of_range());
+	}
+	let fixed = |duration: Option<Duration>| {
+		duration
+			.map(|duration| Period::Fixed(duration, Anchor::Start))
+			.ok_or_else(out_of_range)
+	};
+	let months = |per: i64| {
+		let last = i64::from(NaiveDate::MAX.year()) * 12;
+		num.checked_mul(per * 2)
+			.filter(|months| *months <= last)
+			.and_then(|_| i32::try_from(num).ok())
+			.ok_or_else(out_of_range)
+	};
 
 	let period = match dim {
-		Dimension::Second => Period::Fixed(Duration::seconds(num)),
-		Dimension::Minute => Period::Fixed(Duration::minutes(num)),
-		Dimension::Hour => Period::Fixed(Duration::hours(num)),
-		Dimension::Day => Period::Fixed(Duration::days(num)),
-		Dimension::Week => Period::Fixed(Duration::weeks(num)),
-		Dimension::Month => Period::Month(num as i32),
-		Dimension::Quarter => Period::Quarter(num as i32),
-		Dimension::Year => Period::Year(num as i32),
+		Dimension::Second => fixed(Duration::try_seconds(num))?,
+		Dimension::Minute => fixed(Duration::try_minutes(num))?,
+		Dimension::Hour => fixed(Duration::try_hours(num))?,
+		Dimension::Day => fixed(Duration::try_days(num))?,
+		Dimension::Week => fixed(Duration::try_weeks(num))?,
+		Dimension::Month => Period::Month(months(1)?),
+		Dimension::Quarter => Period::Quarter(months(3)?, align),
+		Dimension::Year => Period::Year(months(12)?, align),
 	};
 
 	Ok((input, period))
 }
 
-pub fn parse_period_inner(input: &str) -> ParseResult<Period> {
-	let res = parse_numeric_period(input);
+pub fn parse_period_inner(input: &str) -> ParseResult<'_, Period> {
+	// Goes first, otherwise "week 10" is taken for a week long period
+	let res = parse_week(input);
 	if res.is_ok() {
 		return res;
 	}
 
+	// Numbers out of range fail rather than trying other periods
+	let res = parse_numeric_period(input);
+	if !matches!(res, Err(nom::Err::Error(_))) {
+		return res;
+	}
+
 	let res = parse_weekday(input);
 	if res.is_ok() {
 		return res;
 	}
 
+	if let Ok((input, month)) = parse_month(input) {
+		return Ok((input, Period::MonthOfYear(month)));
+	}
+
 	Err(ParseError::Unsupported.into_fail(input))
 }
 
-pub fn parse_period(input: &str) -> ParseResult<Period> {
-	let (input, ord) = parse_ordinal(input)?;
+/// Parse "2nd ", "last " or "2nd to last ", positions from the end are
+/// negative
+fn parse_position(input: &str) -> ParseResult<'_, Option<i32>> {
+	let (rest, ord) = parse_ordinal(input)?;
+	let ord = match ord {
+		Some(ord) => Some(
+			i32::try_from(ord)
+				.map_err(|_| ParseError::OutOfRange.into_fail(input))?,
+		),
+		None => None,
+	};
+	let input = rest;
+	let (input, last) = opt(tuple((
+		opt(tuple((tag("to"), space1))),
+		tag("last"),
+		space1,
+	)))(input)?;
+
+	let position = match (ord, last) {
+		(None, None) => None,
+		(Some(ord), None) => Some(ord),
+		(None, Some(_)) => Some(-1),
+		(Some(ord), Some(_)) => Some(-ord),
+	};
+
+	Ok((input, position))
+}
+
+/// Parse " of the month", " of quarter" and similar
+fn parse_of(input: &str) -> ParseResult<'_, Of> {
+	let (input, _) = space1(input)?;
+	let (input, _) = tag("of")(input)?;
+	let (input, _) = space1(input)?;
+	let (input, _) = opt(tuple((tag("the"), space1)))(input)?;
+	let (input, of) = alpha1(input)?;
+	match of {
+		"month" => Ok((input, Of::Month)),
+		"quarter" => Ok((input, Of::Quarter)),
+		"year" => Ok((input, Of::Year)),
+		_ => Err(ParseError::Unsupported.into_fail(input)),
+	}
+}
+
+/// Parse " on the clock" after a fixed period. Periods are anchored at the
+/// date a schedule is starting on otherwise, see [`Every::starting`].
+fn parse_anchor(input: &str) -> ParseResult<'_, Anchor> {
+	let (input, _) = space1(input)?;
+	map(tag("on the clock"), |_| Anchor::Clock)(input)
+}
+
+pub fn parse_period(input: &str) -> ParseResult<'_, Period> {
+	let start = input;
+	let (input, position) = parse_position(input)?;
 	let (input, inner) = parse_period_inner(input)?;
 
-	match ord {
-		None => Ok((input, inner)),
-		Some(ord) => Ok((input, Period::Ordinal(ord, Box::new(inner)))),
+	let (input, inner) = match inner {
+		Period::Fixed(duration, Anchor::Start) => {
+			let (input, anchor) = opt(parse_anchor)(input)?;
+			let anchor = anchor.unwrap_or(Anchor::Start);
+			(input, Period::Fixed(duration, anchor))
+		}
+		inner => (input, inner),
+	};
+
+	match (position, inner) {
+		(None, inner) => Ok((input, inner)),
+		// An ordinal weekday is counted within the month by default
+		(Some(nth), Period::DayOfWeek(day)) => {
+			let (input, of) = opt(parse_of)(input)?;
+			let of = of.unwrap_or(Of::Month);
+			// No period has a 0th or a 6th monday of the month
+			if nth == 0 || nth.abs() > max_nth(of) {
+				return Err(ParseError::OutOfRange.into_fail(start));
+			}
+			Ok((input, Period::NthWeekday(nth, day, of)))
+		}
+		(Some(nth), inner) if nth > 0 => {
+			Ok((input, Period::Ordinal(nth as usize, Box::new(inner))))
+		}
+		(Some(_), _) => Err(ParseError::Unsupported.into_fail(input)),
 	}
 }
 
-pub fn parse_every(input: &str) -> ParseResult<Every> {
+pub fn parse_every(input: &str) -> ParseResult<'_, Every> {
 	let (input, _) = tag("every")(input)?;
 	let (input, _) = space1(input)?;
-	let (input, res) = parse_chain(input, |input| parse_period(input))?;
+	let (input, res) = parse_chain(input, parse_period)?;
 	Ok((input, Every::new(res)))
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use chrono::Weekday;
 
 	#[test]
 	fn parse_every_10_days() {
 		assert_eq!(
 			parse_every("every 10 days").unwrap().1,
-			Every::new(vec![Period::Fixed(Duration::days(10))])
+			Every::new(vec![Period::Fixed(Duration::days(10), Anchor::Start)])
 		)
 	}
 
@@ -83,7 +207,55 @@ mod tests {
 	fn parse_every_2nd_2_years() {
 		assert_eq!(
 			parse_every("every 2nd 2 years").unwrap().1,
-			Every::new(vec![Period::Ordinal(2, Box::new(Period::Year(2)))])
+			Every::new(vec![Period::Ordinal(
+				2,
+				Box::new(Period::Year(2, Align::default()))
+			)])
 		)
 	}
+
+	#[test]
+	fn parse_every_2nd_to_last_friday_of_the_quarter() {
+		assert_eq!(
+			parse_every("every 2nd to last friday of the quarter")
+				.unwrap()
+				.1,
+			Every::new(vec![Period::NthWeekday(-2, Weekday::Fri, Of::Quarter)])
+		);
+		assert_eq!(
+			parse_every("every last sun").unwrap().1,
+			Every::new(vec![Period::NthWeekday(-1, Weekday::Sun, Of::Month)])
+		);
+	}
+
+	#[test]
+	fn parse_every_march_and_september() {
+		assert_eq!(
+			parse_every("every march and sep").unwrap().1,
+			Every::new(vec![
+				Period::MonthOfYear(chrono::Month::March),
+				Period::MonthOfYear(chrono::Month::September)
+			])
+		);
+	}
+
+	#[test]
+	fn parse_every_quarter() {
+		assert_eq!(
+			parse_every("every quarter").unwrap().1,
+			Every::new(vec![Period::Quarter(1, Align::default())])
+		);
+		assert_eq!(
+			parse_every("every rolling 2 q").unwrap().1,
+			Every::new(vec![Period::Quarter(2, Align::Rolling)])
+		);
+	}
+
+	#[test]
+	fn parse_every_2h_on_the_clock() {
+		assert_eq!(
+			parse_every("every 2h on the clock").unwrap().1,
+			Every::new(vec![Period::Fixed(Duration::hours(2), Anchor::Clock)])
+		);
+	}
 }
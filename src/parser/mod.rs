@@ -1,8 +1,195 @@
+mod business;
+mod date;
+mod day;
 mod dimension;
 mod error;
 mod every;
+mod month;
+mod offset;
 mod ordinal;
+mod relative;
 mod schedule;
 mod time;
+mod timeframe;
 mod utils;
+mod week;
 mod weekday;
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use nom::combinator::all_consuming;
+
+use crate::schedule::Schedule;
+
+pub use self::error::{Error, ParseError};
+
+/// Parse a human readable description into a [`Schedule`].
+pub fn parse(input: &str) -> Result<Schedule, Error> {
+	parse_in(input, Tz::UTC)
+}
+
+/// Parse a human readable description into a [`Schedule`] evaluated in the
+/// given time zone, dates and times in the description are local to it.
+pub fn parse_in(input: &str, tz: Tz) -> Result<Schedule, Error> {
+	parse_relative_to(input, tz, Utc::now())
+}
+
+/// Parse a human readable description into a [`Schedule`] evaluated in the
+/// given time zone, relative expressions like "tomorrow" or "in 2 hours"
+/// are resolved against `now`.
+pub fn parse_relative_to(
+	input: &str,
+	tz: Tz,
+	now: DateTime<Utc>,
+) -> Result<Schedule, Error> {
+	let parse = |input| schedule::parse_schedule(input, tz, now);
+	match all_consuming(parse)(input) {
+		Ok((_, schedule)) => Ok(schedule),
+		Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+			Err(Error::new(input, e))
+		}
+		// All the parsers operate on complete input
+		Err(nom::Err::Incomplete(_)) => unreachable!(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::interval::Interval;
+	use chrono::{DateTime, Utc};
+
+	#[test]
+	fn parse_reports_offset() {
+		let err = parse("every fortnight").unwrap_err();
+		assert_eq!(err.offset(), 6);
+	}
+
+	#[test]
+	fn holidays_need_a_known_country() {
+		let err = parse("every weekday except public holidays").unwrap_err();
+		assert!(matches!(err.kind(), ParseError::Unsupported));
+		assert_eq!(err.offset(), 21);
+
+		let err = parse("3 business days before every monday").unwrap_err();
+		assert!(matches!(err.kind(), ParseError::Unsupported));
+		assert_eq!(err.offset(), 2);
+	}
+
+	#[test]
+	fn parse_rejects_schedules_without_occurrences() {
+		for input in &["once", "except us holidays", "for 3 times"] {
+			let err = parse(input).unwrap_err();
+			assert!(matches!(err.kind(), ParseError::Unsupported), "{}", input);
+			assert_eq!(err.offset(), 0);
+		}
+	}
+
+	#[test]
+	fn parse_rejects_values_out_of_range() {
+		for input in &[
+			"every 0 minutes",
+			"every day lasting 99999999999 weeks",
+			"every day for 2000000000 quarters",
+			"every day for 400000000 years",
+			"every 9999999999999999 weeks",
+			"every 999999999 years",
+			"every 999999 years",
+			"every 200000 years",
+			"every 0th monday",
+			"every 6th monday",
+			"every 6th to last monday",
+			"every 15th monday of the quarter",
+			"every 4294967297th monday",
+			"9999999999999999 days after every monday",
+			"9223372036854775807 weeks after every monday",
+			"9223372036854775807 us business days before every monday",
+			"in 99999999 days",
+			"in 2000000000 years",
+			"in 4000000000 hours",
+		] {
+			let err = parse(input).unwrap_err();
+			assert!(matches!(err.kind(), ParseError::OutOfRange), "{}", input);
+		}
+	}
+
+	#[test]
+	fn spans_past_the_last_date_have_no_end() {
+		let schedule = parse("every day for 4000000000 days").unwrap();
+		assert!(schedule
+			.iter_within(Interval::from(
+				"2019-01-01T00:00:00Z".parse().unwrap()
+			))
+			.next()
+			.is_some());
+	}
+
+	#[test]
+	fn parse_in_berlin() {
+		let schedule =
+			parse_in("every friday at 10am", chrono_tz::Europe::Berlin)
+				.unwrap();
+
+		let utc: Vec<DateTime<Utc>> = schedule
+			.iter_within(Interval::from(
+				"2019-10-15T00:00:00Z".parse().unwrap(),
+			))
+			.take(3)
+			.map(|interval| interval.from.with_timezone(&Utc))
+			.collect();
+
+		assert_eq!(
+			utc,
+			vec![
+				"2019-10-18T08:00:00Z".parse::<DateTime<Utc>>().unwrap(),
+				"2019-10-25T08:00:00Z".parse().unwrap(),
+				"2019-11-01T09:00:00Z".parse().unwrap(),
+			]
+		);
+	}
+
+	#[test]
+	fn parse_relative_to_now() {
+		let now = "2019-01-02T15:00:00Z".parse().unwrap();
+		let first = |input| {
+			parse_relative_to(input, Tz::UTC, now)
+				.unwrap()
+				.iter_within(Interval::from(now))
+				.next()
+				.map(|interval| interval.from.with_timezone(&Utc))
+		};
+
+		assert_eq!(
+			first("tomorrow at 9am"),
+			"2019-01-03T09:00:00Z".parse().ok()
+		);
+		assert_eq!(
+			first("next friday at 10am"),
+			"2019-01-04T10:00:00Z".parse().ok()
+		);
+		assert_eq!(first("in 10 minutes"), "2019-01-02T15:10:00Z".parse().ok());
+		assert_eq!(
+			first("on 2020-03-01 at 14:00"),
+			"2020-03-01T14:00:00Z".parse().ok()
+		);
+		assert_eq!(first("today at 9am"), None);
+	}
+
+	#[test]
+	fn once_is_the_next_occurrence_from_now() {
+		let now = "2019-01-02T15:00:00Z".parse().unwrap();
+		let schedule =
+			parse_relative_to("once at 20:30", Tz::UTC, now).unwrap();
+		let from = |from: &str| -> Vec<DateTime<Utc>> {
+			schedule
+				.iter_within(Interval::from(from.parse().unwrap()))
+				.map(|interval| interval.from.with_timezone(&Utc))
+				.collect()
+		};
+
+		let once: DateTime<Utc> = "2019-01-02T20:30:00Z".parse().unwrap();
+		assert_eq!(from("2018-06-01T00:00:00Z"), vec![once]);
+		assert_eq!(from("2019-01-02T18:00:00Z"), vec![once]);
+		assert!(from("2019-06-01T00:00:00Z").is_empty());
+	}
+}
@@ -0,0 +1,51 @@
+use chrono::Month;
+use nom::character::complete::alpha1;
+
+use crate::period::Period;
+
+use super::error::{ParseError, ParseResult};
+use super::timeframe::parse_range;
+
+pub fn parse_month(input: &str) -> ParseResult<'_, Month> {
+	let (input, name) = alpha1(input)?;
+	let month = match name {
+		"january" | "jan" => Month::January,
+		"february" | "feb" => Month::February,
+		"march" | "mar" => Month::March,
+		"april" | "apr" => Month::April,
+		"may" => Month::May,
+		"june" | "jun" => Month::June,
+		"july" | "jul" => Month::July,
+		"august" | "aug" => Month::August,
+		"september" | "sep" | "sept" => Month::September,
+		"october" | "oct" => Month::October,
+		"november" | "nov" => Month::November,
+		"december" | "dec" => Month::December,
+		_ => return Err(ParseError::Unsupported.into_err(input)),
+	};
+
+	Ok((input, month))
+}
+
+/// Parse "from june to august" or "between november and february"
+pub fn parse_months(input: &str) -> ParseResult<'_, Period> {
+	let (input, (from, to)) = parse_range(input, parse_month)?;
+	Ok((input, Period::Months(from, to)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn months() {
+		assert_eq!(
+			parse_months("from june to aug").unwrap(),
+			("", Period::Months(Month::June, Month::August))
+		);
+		assert_eq!(
+			parse_months("between november and february").unwrap(),
+			("", Period::Months(Month::November, Month::February))
+		);
+	}
+}
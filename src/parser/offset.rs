@@ -0,0 +1,122 @@
+use std::convert::TryFrom;
+
+use chrono::Duration;
+use chrono_tz::Tz;
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::{digit1, space0, space1},
+	combinator::{map, opt},
+	sequence::preceded,
+};
+
+use crate::every::Every;
+use crate::ext::Offset;
+use crate::types::Dimension;
+
+use super::business::parse_business_days;
+use super::day::parse_days_of_month;
+use super::dimension::parse_dimension;
+use super::error::{ParseError, ParseResult};
+
+/// Parse "3 business days before", "15 minutes after" or "2 days before the
+/// last day of the month", which moves the occurrences of the schedule
+pub fn parse_offset(
+	input: &str,
+	tz: Tz,
+) -> ParseResult<'_, (Offset, Option<Every>)> {
+	let (input, num) = digit1(input)?;
+	let (input, _) = space0(input)?;
+	let num: i64 = num
+		.parse()
+		.map_err(|e| ParseError::InvalidNumericValue(e).into_fail(input))?;
+
+	let out_of_range = || ParseError::OutOfRange.into_fail(input);
+	let (input, offset) = match parse_business_days(input, tz) {
+		Ok((input, calendar)) => (input, Offset::BusinessDays(num, calendar)),
+		Err(nom::Err::Error(_)) => {
+			let (input, dim) = parse_dimension(input)?;
+			let duration = |d: Option<Duration>| {
+				d.map(Offset::Duration).ok_or_else(out_of_range)
+			};
+			let months = |n: Option<i64>| {
+				n.and_then(|n| i32::try_from(n).ok())
+					.map(Offset::Months)
+					.ok_or_else(out_of_range)
+			};
+			let offset = match dim {
+				Dimension::Second => duration(Duration::try_seconds(num))?,
+				Dimension::Minute => duration(Duration::try_minutes(num))?,
+				Dimension::Hour => duration(Duration::try_hours(num))?,
+				Dimension::Day => Offset::Days(num),
+				Dimension::Week => {
+					Offset::Days(num.checked_mul(7).ok_or_else(out_of_range)?)
+				}
+				Dimension::Month => months(Some(num))?,
+				Dimension::Quarter => months(num.checked_mul(3))?,
+				Dimension::Year => months(num.checked_mul(12))?,
+			};
+			(input, offset)
+		}
+		Err(e) => return Err(e),
+	};
+
+	// Offsets moving further than a duration can express move everything
+	// out of the range of dates
+	if offset.margin().is_none() {
+		return Err(out_of_range());
+	}
+
+	let (input, _) = space1(input)?;
+	let (input, before) = alt((
+		map(tag("before"), |_| true),
+		map(tag("after"), |_| false),
+	))(input)?;
+
+	let offset = if before {
+		match offset {
+			Offset::Duration(d) => Offset::Duration(-d),
+			Offset::Days(n) => Offset::Days(-n),
+			Offset::Months(n) => Offset::Months(-n),
+			Offset::BusinessDays(n, calendar) => {
+				Offset::BusinessDays(-n, calendar)
+			}
+		}
+	} else {
+		offset
+	};
+
+	// The days of the month can follow directly, the other items of the
+	// schedule are separate clauses
+	let (input, days) = opt(preceded(space1, parse_days_of_month))(input)?;
+
+	Ok((input, (offset, days)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::calendar::Calendar;
+	use crate::day::ShortMonth;
+	use crate::period::Period;
+
+	#[test]
+	fn offsets() {
+		assert_eq!(
+			parse_offset("15 minutes after", Tz::UTC).unwrap(),
+			("", (Offset::Duration(Duration::minutes(15)), None))
+		);
+		assert_eq!(
+			parse_offset("3 us business days before the last day", Tz::UTC)
+				.unwrap()
+				.1,
+			(
+				Offset::BusinessDays(-3, Calendar::us_federal()),
+				Some(Every::new(vec![Period::DayOfMonth(
+					-1,
+					ShortMonth::Skip
+				)]))
+			)
+		);
+	}
+}
@@ -8,7 +8,7 @@ use nom::{
 
 use super::error::{ParseError, ParseResult};
 
-pub fn parse_ordinal(input: &str) -> ParseResult<Option<usize>> {
+pub fn parse_ordinal(input: &str) -> ParseResult<'_, Option<usize>> {
 	let (input, nth): (&str, Option<(&str, &str, &str, &str)>) = opt(tuple((
 		digit1,
 		alt((space0, tag("-"))),
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Datelike, Days, Duration, Months, NaiveDate, Utc};
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::{digit1, space0, space1},
+	combinator::map,
+};
+
+use crate::date::{Dates, Moment};
+use crate::interval::Timeline;
+use crate::types::Dimension;
+use crate::zone::Zone;
+
+use super::date::parse_date;
+use super::dimension::parse_dimension;
+use super::error::{ParseError, ParseResult};
+use super::utils::parse_chain;
+use super::weekday::parse_day_of_week;
+
+/// Parse "in 2 hours" or "in 3 months" into the instant that far from now
+fn parse_in<'a>(
+	input: &'a str,
+	now: &DateTime<Zone>,
+) -> ParseResult<'a, DateTime<Utc>> {
+	let (input, _) = tag("in")(input)?;
+	let (input, _) = space1(input)?;
+	let (input, num) = digit1(input)?;
+	let (input, _) = space0(input)?;
+	let (input, dim) = parse_dimension(input)?;
+	let num: u32 = num
+		.parse()
+		.map_err(|e| ParseError::InvalidNumericValue(e).into_fail(input))?;
+
+	let n = i64::from(num);
+	let out_of_range = || ParseError::OutOfRange.into_fail(input);
+	let months = |months: Option<u32>| {
+		let local =
+			now.naive_local().checked_add_months(Months::new(months?))?;
+		Some(now.timezone().localize(&local))
+	};
+
+	let at = match dim {
+		Dimension::Second => {
+			Duration::try_seconds(n).and_then(|d| now.checked_add_signed(d))
+		}
+		Dimension::Minute => {
+			Duration::try_minutes(n).and_then(|d| now.checked_add_signed(d))
+		}
+		Dimension::Hour => {
+			Duration::try_hours(n).and_then(|d| now.checked_add_signed(d))
+		}
+		// Calendar units keep the wall clock time
+		Dimension::Day | Dimension::Week => {
+			let days = if let Dimension::Week = dim { n * 7 } else { n };
+			now.naive_local()
+				.checked_add_days(Days::new(days as u64))
+				.map(|local| now.timezone().localize(&local))
+		}
+		Dimension::Month => months(Some(num)),
+		Dimension::Quarter => months(num.checked_mul(3)),
+		Dimension::Year => months(num.checked_mul(12)),
+	};
+	let at = at.ok_or_else(out_of_range)?;
+
+	Ok((input, at.with_timezone(&Utc)))
+}
+
+/// Parse "next friday" into the first friday after today
+fn parse_next<'a>(
+	input: &'a str,
+	today: NaiveDate,
+) -> ParseResult<'a, NaiveDate> {
+	let (input, _) = tag("next")(input)?;
+	let (input, _) = space1(input)?;
+	let (input, day) = parse_day_of_week(input)?;
+	let ahead = (7 + day.num_days_from_monday()
+		- today.weekday().num_days_from_monday())
+		% 7;
+	let ahead = if ahead == 0 { 7 } else { ahead };
+
+	Ok((input, today + Duration::days(i64::from(ahead))))
+}
+
+/// Parse "on 2020-03-01" or "on 2020-03-01 and 2020-03-05"
+pub fn parse_on_dates(input: &str) -> ParseResult<'_, Dates> {
+	let (input, _) = tag("on")(input)?;
+	let (input, _) = space1(input)?;
+	let (input, dates) = parse_chain(input, parse_date)?;
+	Ok((input, Dates::new(dates)))
+}
+
+fn day(date: NaiveDate) -> Box<dyn Timeline> {
+	Box::new(Dates::new(vec![date]))
+}
+
+/// Parse a one-off expression relative to the reference time, like
+/// "tomorrow", "next friday", "in 2 hours" or an absolute date
+pub fn parse_relative<'a>(
+	input: &'a str,
+	now: &DateTime<Zone>,
+) -> ParseResult<'a, Box<dyn Timeline>> {
+	let today = now.date_naive();
+
+	alt((
+		map(tag("today"), move |_| day(today)),
+		map(tag("tomorrow"), move |_| day(today + Duration::days(1))),
+		map(move |input| parse_next(input, today), day),
+		map(
+			|input| parse_in(input, now),
+			|at| -> Box<dyn Timeline> { Box::new(Moment::new(at)) },
+		),
+		map(parse_on_dates, |dates| -> Box<dyn Timeline> {
+			Box::new(dates)
+		}),
+	))(input)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::Weekday;
+	use chrono_tz::Tz;
+
+	fn now() -> DateTime<Zone> {
+		"2019-01-02T15:00:00Z"
+			.parse::<DateTime<Utc>>()
+			.unwrap()
+			.with_timezone(&Zone::from(Tz::UTC))
+	}
+
+	#[test]
+	fn next_friday() {
+		let date = NaiveDate::from_ymd_opt(2019, 1, 4).unwrap();
+		assert_eq!(parse_next("next fri", now().date_naive()).unwrap().1, date);
+		assert_eq!(
+			parse_next("next fri", date).unwrap().1,
+			date + Duration::days(7)
+		);
+		assert_eq!(date.weekday(), Weekday::Fri);
+	}
+
+	#[test]
+	fn in_2_hours_and_1_month() {
+		assert_eq!(
+			parse_in("in 2 hours", &now()).unwrap().1,
+			"2019-01-02T17:00:00Z".parse::<DateTime<Utc>>().unwrap()
+		);
+		assert_eq!(
+			parse_in("in 1 month", &now()).unwrap().1,
+			"2019-02-02T15:00:00Z".parse::<DateTime<Utc>>().unwrap()
+		);
+	}
+}
@@ -1,22 +1,761 @@
-use nom::multi::many1;
+use std::convert::TryFrom;
 
-use crate::schedule::Schedule;
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::{digit1, space0, space1},
+	combinator::{map, opt},
+	sequence::preceded,
+};
 
+use crate::date::DateSpec;
+use crate::every::Every;
+use crate::ext::Offset;
+use crate::interval::{Interval, Timeline};
+use crate::schedule::{Schedule, Span};
+use crate::types::Dimension;
+use crate::zone::Zone;
+
+use super::business::parse_on_adjusted;
+use super::date::{parse_also, parse_datetime, parse_except};
+use super::day::parse_on;
+use super::dimension::parse_dimension;
 use super::error::{ParseError, ParseResult};
 use super::every::parse_every;
+use super::month::parse_months;
+use super::offset::parse_offset;
+use super::relative::parse_relative;
+use super::time::{parse_at, parse_at_minutes, parse_at_seconds};
+use super::timeframe::{parse_timeframe, parse_weekframe};
+use super::week::parse_in_week;
+use super::weekday::parse_on_weekdays;
+
+enum Clause {
+	Every(Every),
+	Item(Box<dyn Timeline>),
+	/// Times of the day, or of the hour or the minute
+	At(Box<dyn Timeline>),
+	For(For),
+	Starting(NaiveDateTime),
+	Until(NaiveDateTime),
+	Except(Vec<DateSpec>),
+	Also(Vec<DateSpec>),
+	Offset(Offset, Option<Every>),
+	Lasting(Duration),
+	Once,
+}
+
+enum For {
+	/// How long the schedule lasts
+	Span(Span),
+	/// How many occurrences there are
+	Count(usize),
+}
+
+fn parse_starting(input: &str) -> ParseResult<'_, NaiveDateTime> {
+	let (input, _) = tag("starting")(input)?;
+	let (input, _) = space1(input)?;
+	parse_datetime(input)
+}
+
+fn parse_until(input: &str) -> ParseResult<'_, NaiveDateTime> {
+	let (input, _) = tag("until")(input)?;
+	let (input, _) = space1(input)?;
+	parse_datetime(input)
+}
 
-pub fn parse_schedule(input: &str) -> ParseResult<Schedule> {
-	let (input, items) = many1(parse_every)(input)?;
-	Err(ParseError::Unsupported.into_fail(input))
+/// Parse "for 10 times", "for 2 hours" or "for 3 months", which limit the
+/// schedule. Right after a time a duration is how long every occurrence
+/// lasts instead, see [`parse_lasting`].
+fn parse_for(input: &str) -> ParseResult<'_, For> {
+	let (input, _) = tag("for")(input)?;
+	let (input, _) = space1(input)?;
+	let (input, num) = digit1(input)?;
+	let (input, _) = space0(input)?;
+	let num: u32 = num
+		.parse()
+		.map_err(|e| ParseError::InvalidNumericValue(e).into_fail(input))?;
+
+	let times: ParseResult<'_, &str> =
+		alt((tag("times"), tag("time"), tag("occurrences")))(input);
+	if let Ok((input, _)) = times {
+		return Ok((input, For::Count(num as usize)));
+	}
+
+	let (rest, dim) = parse_dimension(input)?;
+	let n = i64::from(num);
+	let months = |by: u32| {
+		num.checked_mul(by)
+			.map(|months| For::Span(Span::Months(months)))
+			.ok_or_else(|| ParseError::OutOfRange.into_fail(input))
+	};
+
+	let value = match dim {
+		Dimension::Second => For::Span(Span::Duration(Duration::seconds(n))),
+		Dimension::Minute => For::Span(Span::Duration(Duration::minutes(n))),
+		Dimension::Hour => For::Span(Span::Duration(Duration::hours(n))),
+		Dimension::Day => For::Span(Span::Days(u64::from(num))),
+		Dimension::Week => For::Span(Span::Days(u64::from(num) * 7)),
+		Dimension::Month => months(1)?,
+		Dimension::Quarter => months(3)?,
+		Dimension::Year => months(12)?,
+	};
+
+	Ok((rest, value))
+}
+
+/// The duration of a span that has a fixed length
+fn lasting(span: Span) -> Option<Duration> {
+	match span {
+		Span::Duration(duration) => Some(duration),
+		Span::Days(days) => Duration::try_days(i64::try_from(days).ok()?),
+		Span::Months(_) => None,
+	}
+}
+
+/// Parse "lasting 1 hour", how long every occurrence lasts
+fn parse_lasting(input: &str) -> ParseResult<'_, Duration> {
+	let (input, _) = tag("lasting")(input)?;
+	let (input, _) = space1(input)?;
+	let (input, num) = digit1(input)?;
+	let (input, _) = space0(input)?;
+	let num: i64 = num
+		.parse()
+		.map_err(|e| ParseError::InvalidNumericValue(e).into_fail(input))?;
+
+	let (rest, dim) = parse_dimension(input)?;
+	let duration = match dim {
+		Dimension::Second => Duration::try_seconds(num),
+		Dimension::Minute => Duration::try_minutes(num),
+		Dimension::Hour => Duration::try_hours(num),
+		Dimension::Day => Duration::try_days(num),
+		Dimension::Week => Duration::try_weeks(num),
+		// Months and years don't have a fixed length
+		Dimension::Month | Dimension::Quarter | Dimension::Year => {
+			return Err(ParseError::Unsupported.into_fail(input));
+		}
+	};
+
+	match duration {
+		Some(duration) => Ok((rest, duration)),
+		None => Err(ParseError::OutOfRange.into_fail(input)),
+	}
+}
+
+fn parse_clause<'a>(
+	input: &'a str,
+	now: &DateTime<Zone>,
+) -> ParseResult<'a, Clause> {
+	alt((
+		map(parse_every, Clause::Every),
+		map(parse_at_minutes, |at| Clause::At(Box::new(at))),
+		map(parse_at_seconds, |at| Clause::At(Box::new(at))),
+		map(parse_at, |at| Clause::At(Box::new(at))),
+		map(
+			|input| parse_on_adjusted(input, now.timezone().tz()),
+			Clause::Item,
+		),
+		map(parse_on, |on| Clause::Item(Box::new(on))),
+		map(parse_on_weekdays, |on| Clause::Item(Box::new(on))),
+		map(parse_in_week, |weeks| Clause::Item(Box::new(weeks))),
+		map(parse_months, |months| Clause::Item(Box::new(months))),
+		map(parse_weekframe, |frame| Clause::Item(Box::new(frame))),
+		map(parse_timeframe, |frame| Clause::Item(Box::new(frame))),
+		map(parse_starting, Clause::Starting),
+		map(parse_until, Clause::Until),
+		map(parse_for, Clause::For),
+		map(parse_lasting, Clause::Lasting),
+		map(
+			|input| parse_except(input, now.timezone().tz()),
+			Clause::Except,
+		),
+		map(|input| parse_also(input, now.timezone().tz()), Clause::Also),
+		map(tag("once"), |_| Clause::Once),
+		map(
+			|input| parse_offset(input, now.timezone().tz()),
+			|(offset, days)| Clause::Offset(offset, days),
+		),
+		map(|input| parse_relative(input, now), Clause::Item),
+	))(input)
+}
+
+/// Parse a schedule in the given time zone, relative expressions like
+/// "tomorrow" are resolved against `now`
+pub fn parse_schedule(
+	input: &str,
+	tz: Tz,
+	now: DateTime<Utc>,
+) -> ParseResult<'_, Schedule> {
+	let zone = Zone::from(tz);
+	let now = now.with_timezone(&zone);
+	let parse_clause = |input| parse_clause(input, &now);
+
+	let description = input;
+	let (mut input, clause) = parse_clause(input)?;
+	let mut clauses = vec![clause];
+
+	loop {
+		let (i, clause) = opt(preceded(space1, parse_clause))(input)?;
+		match clause {
+			Some(clause) => {
+				clauses.push(clause);
+				input = i;
+			}
+			None => break,
+		}
+	}
+
+	// Fixed periods step from the start of the schedule, like they do from
+	// the start of the iteration without one
+	let start = clauses.iter().rev().find_map(|clause| match clause {
+		Clause::Starting(start) => Some(*start),
+		_ => None,
+	});
+
+	let mut items: Vec<Box<dyn Timeline>> = vec![];
+	let mut from = None;
+	let mut to = None;
+	let mut once = false;
+	let mut duration = None;
+	let mut span = None;
+	let mut count = None;
+	let mut exclusions = vec![];
+	let mut inclusions = vec![];
+	let mut offset = None;
+	let mut after_at = false;
+
+	for clause in clauses {
+		let at = matches!(clause, Clause::At(_));
+		match clause {
+			Clause::Every(every) => items.push(Box::new(match start {
+				Some(start) => every.starting(start),
+				None => every,
+			})),
+			Clause::Item(item) | Clause::At(item) => items.push(item),
+			// "at 10am for 1 hour" is how long the occurrences last
+			Clause::For(For::Span(s)) if after_at && lasting(s).is_some() => {
+				duration = lasting(s);
+			}
+			Clause::Starting(start) => from = Some(zone.localize(&start)),
+			Clause::Until(end) => to = Some(zone.localize(&end)),
+			Clause::Once => once = true,
+			Clause::Lasting(d) => duration = Some(d),
+			Clause::For(For::Span(s)) => span = Some(s),
+			Clause::For(For::Count(n)) => count = Some(n),
+			Clause::Except(dates) => exclusions.extend(dates),
+			Clause::Also(dates) => inclusions.extend(dates),
+			Clause::Offset(by, days) => {
+				offset = Some(by);
+				if let Some(days) = days {
+					items.push(Box::new(days));
+				}
+			}
+		}
+		after_at = at;
+	}
+
+	// Nothing would ever happen without something to repeat or to add
+	if items.is_empty() && inclusions.is_empty() {
+		return Err(ParseError::Unsupported.into_fail(description));
+	}
+
+	// A single occurrence is the next one from now, unless the schedule
+	// starts elsewhere
+	if once {
+		from = from.or(Some(now));
+	}
+
+	let bounds = match (from, to) {
+		(None, None) => None,
+		(from, to) => Some(Interval {
+			from: from.unwrap_or_else(|| {
+				DateTime::<Utc>::MIN_UTC.with_timezone(&zone)
+			}),
+			to,
+		}),
+	};
+
+	let mut schedule = Schedule::new(items, bounds)
+		.with_timezone(tz)
+		.with_exclusions(exclusions)
+		.with_inclusions(inclusions);
+	if let Some(duration) = duration {
+		schedule = schedule.with_duration(duration);
+	}
+	if let Some(span) = span {
+		schedule = schedule.with_span(span);
+	}
+	if let Some(count) = count {
+		schedule = schedule.with_count(count);
+	}
+	if let Some(offset) = offset {
+		schedule = schedule.with_offset(offset);
+	}
+
+	if once {
+		Ok((input, schedule.with_count(1)))
+	} else {
+		Ok((input, schedule))
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
-	// at 3:00am
-	// every sat, sun at 3:00am
-	// every friday at 5am
-	// every 2nd friday at 10am
-	// every sunday from 3:00 to 4:00 until 2010-30-10T20:00
-	// once at 20:30
+	use insta::assert_debug_snapshot_matches;
+
+	fn now() -> DateTime<Utc> {
+		"2019-01-01T00:00:00Z".parse().unwrap()
+	}
+
+	fn occurrences(input: &str) -> Vec<Interval> {
+		let (rest, schedule) = parse_schedule(input, Tz::UTC, now()).unwrap();
+		assert_eq!(rest, "");
+
+		schedule
+			.iter_within(Interval::from(
+				"2019-01-01T00:00:00Z".parse().unwrap(),
+			))
+			.take(5)
+			.collect()
+	}
+
+	#[test]
+	fn at_3_am() {
+		assert_debug_snapshot_matches!("at_3_am", occurrences("at 3:00am"));
+	}
+
+	#[test]
+	fn every_sat_sun_at_3_am() {
+		assert_debug_snapshot_matches!(
+			"every_sat_sun_at_3_am",
+			occurrences("every sat, sun at 3:00am")
+		);
+	}
+
+	#[test]
+	fn every_2nd_friday_at_10_am() {
+		assert_debug_snapshot_matches!(
+			"every_2nd_friday_at_10_am",
+			occurrences("every 2nd friday at 10am")
+		);
+	}
+
+	#[test]
+	fn every_sunday_from_3_to_4_until() {
+		assert_debug_snapshot_matches!(
+			"every_sunday_from_3_to_4_until",
+			occurrences(
+				"every sunday from 3:00 to 4:00 until 2019-01-20T20:00"
+			)
+		);
+	}
+
+	#[test]
+	fn until_is_inclusive() {
+		let from = "2019-01-02T00:00:00Z";
+		// An occurrence that starts right at the end still counts
+		assert_eq!(
+			first("every day at 9am until 2019-01-03T09:00", from, 3),
+			vec!["01-02 09:00..01-02 09:00", "01-03 09:00..01-03 09:00"]
+		);
+		assert_eq!(
+			first(
+				"every day at 9am lasting 1h until 2019-01-03T09:00",
+				from,
+				3
+			),
+			vec!["01-02 09:00..01-02 10:00", "01-03 09:00..01-03 10:00"]
+		);
+		// Others are cut at the end
+		assert_eq!(
+			first("every day from 9am to 10am until 2019-01-03T09:30", from, 3),
+			vec!["01-02 09:00..01-02 10:00", "01-03 09:00..01-03 09:30"]
+		);
+		assert_eq!(
+			first("every 30 minutes until 2019-01-02T01:00", from, 4),
+			vec![
+				"01-02 00:00..01-02 00:30",
+				"01-02 00:30..01-02 01:00",
+				"01-02 01:00..01-02 01:00",
+			]
+		);
+	}
+
+	#[test]
+	fn once_at_20_30() {
+		assert_debug_snapshot_matches!(
+			"once_at_20_30",
+			occurrences("once at 20:30")
+		);
+	}
+
+	#[test]
+	fn on_the_1st_and_last_day_at_9_am() {
+		assert_debug_snapshot_matches!(
+			"on_the_1st_and_last_day_at_9_am",
+			occurrences("on the 1st and the last day at 9am")
+		);
+	}
+
+	#[test]
+	fn from_june_to_august_on_the_15th() {
+		assert_debug_snapshot_matches!(
+			"from_june_to_august_on_the_15th",
+			occurrences("from june to august on the 15th")
+		);
+	}
+
+	#[test]
+	fn in_even_weeks_every_monday_at_10_am() {
+		assert_debug_snapshot_matches!(
+			"in_even_weeks_every_monday_at_10_am",
+			occurrences("in even weeks every monday at 10am")
+		);
+	}
+
+	#[test]
+	fn every_weekday_at_15() {
+		assert_debug_snapshot_matches!(
+			"every_weekday_at_15",
+			occurrences("every weekday from 9:00 to 17:00 at :15")
+		);
+	}
+
+	#[test]
+	fn every_15_minutes_from_9_to_5_on_weekdays() {
+		let (_, schedule) = parse_schedule(
+			"every 15 minutes from 9am to 5pm on weekdays",
+			Tz::UTC,
+			now(),
+		)
+		.unwrap();
+
+		// Steps are aligned to the window start even when the iteration
+		// begins in the middle of the window, the step in progress is cut
+		assert_debug_snapshot_matches!(
+			"every_15_minutes_from_9_to_5_on_weekdays",
+			schedule
+				.iter_within(Interval::from(
+					"2019-01-04T16:07:00Z".parse().unwrap(),
+				))
+				.take(5)
+				.collect::<Vec<_>>()
+		);
+	}
+
+	fn first(input: &str, from: &str, n: usize) -> Vec<String> {
+		let (_, schedule) = parse_schedule(input, Tz::UTC, now()).unwrap();
+		schedule
+			.iter_within(Interval::from(from.parse().unwrap()))
+			.take(n)
+			.map(|interval| {
+				let format = |at: DateTime<Zone>| at.format("%m-%d %H:%M");
+				format!(
+					"{}..{}",
+					format(interval.from),
+					format(interval.to.unwrap())
+				)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn rules_within_steps_of_several_units() {
+		let from = "2019-01-01T00:00:00Z";
+		assert_eq!(
+			first("every 2 days at 10:30am", from, 3),
+			vec![
+				"01-01 10:30..01-01 10:30",
+				"01-03 10:30..01-03 10:30",
+				"01-05 10:30..01-05 10:30"
+			]
+		);
+		assert_eq!(
+			first("every 2 weeks at 9am", from, 3),
+			vec![
+				"01-01 09:00..01-01 09:00",
+				"01-15 09:00..01-15 09:00",
+				"01-29 09:00..01-29 09:00"
+			]
+		);
+		assert_eq!(
+			first("every 2 weeks on mondays", from, 3),
+			vec![
+				"01-07 00:00..01-08 00:00",
+				"01-21 00:00..01-22 00:00",
+				"02-04 00:00..02-05 00:00"
+			]
+		);
+		assert_eq!(
+			first("every 3 months on the 1st", from, 3),
+			vec![
+				"01-01 00:00..01-02 00:00",
+				"04-01 00:00..04-02 00:00",
+				"07-01 00:00..07-02 00:00"
+			]
+		);
+		assert_eq!(
+			first("every quarter on the 2nd to last day", from, 3),
+			vec![
+				"01-30 00:00..01-31 00:00",
+				"04-29 00:00..04-30 00:00",
+				"07-30 00:00..07-31 00:00"
+			]
+		);
+		// Steps of a single unit keep all the days within them
+		assert_eq!(
+			days("every month on mondays", from)[..3],
+			["2019-01-07", "2019-01-14", "2019-01-21"]
+		);
+	}
+
+	#[test]
+	fn a_quarter_started_before_the_iteration() {
+		assert_eq!(
+			days("every quarter on the 1st", "2019-02-15T00:00:00Z")[..2],
+			["2019-04-01", "2019-07-01"]
+		);
+	}
+
+	#[test]
+	fn occurrences_in_progress_are_cut() {
+		let monday_noon = "2019-06-03T12:00:00Z";
+		assert_eq!(
+			first("every weekday", monday_noon, 2),
+			vec!["06-03 12:00..06-04 00:00", "06-04 00:00..06-05 00:00"]
+		);
+		assert_eq!(
+			first("every monday", monday_noon, 1),
+			vec!["06-03 12:00..06-04 00:00"]
+		);
+		assert_eq!(
+			first("every month", "2019-06-15T12:00:00Z", 2),
+			vec!["06-15 12:00..07-15 12:00", "07-15 12:00..08-15 12:00"]
+		);
+		assert_eq!(
+			first("every 2 hours", "2019-06-15T12:30:00Z", 2),
+			vec!["06-15 12:30..06-15 14:30", "06-15 14:30..06-15 16:30"]
+		);
+	}
+
+	#[test]
+	fn durations_and_spans() {
+		let noon = "2019-06-03T12:00:00Z";
+		// Occurrences are cut short when the next one starts
+		assert_eq!(
+			first("every 30 minutes lasting 1 hour", noon, 2),
+			vec!["06-03 12:00..06-03 12:30", "06-03 12:30..06-03 13:00"]
+		);
+		assert_eq!(
+			first("every monday at 10am lasting 2 days", noon, 1),
+			vec!["06-10 10:00..06-12 10:00"]
+		);
+		// Right after a time a duration is how long occurrences last
+		assert_eq!(
+			first("every monday at 10am for 1 hour", noon, 2),
+			vec!["06-10 10:00..06-10 11:00", "06-17 10:00..06-17 11:00"]
+		);
+		assert_eq!(
+			first("every monday at 10am for 2 days", noon, 1),
+			vec!["06-10 10:00..06-12 10:00"]
+		);
+		assert_eq!(
+			first("every monday at 10am for 2 months", noon, 2),
+			vec!["06-10 10:00..06-10 10:00", "06-17 10:00..06-17 10:00"]
+		);
+		// Spans limit the schedule whatever their unit
+		assert_eq!(
+			first("every 30 minutes for 1 hour", noon, 3),
+			vec!["06-03 12:00..06-03 12:30", "06-03 12:30..06-03 13:00"]
+		);
+		assert_eq!(
+			first("every 12 hours for 1 day", noon, 3),
+			vec!["06-03 12:00..06-04 00:00", "06-04 00:00..06-04 12:00"]
+		);
+	}
+
+	#[test]
+	fn starting_inside_an_overnight_window() {
+		let after_midnight = "2019-06-03T00:30:00Z";
+		assert_eq!(
+			first("from 23:00 to 1:00", after_midnight, 2),
+			vec!["06-03 00:30..06-03 01:00", "06-03 23:00..06-04 01:00"]
+		);
+		assert_eq!(
+			first("every 15 minutes from 11pm to 1am", after_midnight, 3),
+			vec![
+				"06-03 00:30..06-03 00:45",
+				"06-03 00:45..06-03 01:00",
+				"06-03 23:00..06-03 23:15",
+			]
+		);
+	}
+
+	#[test]
+	fn every_hour_from_10_pm_to_2_am_on_fridays() {
+		assert_debug_snapshot_matches!(
+			"every_hour_from_10_pm_to_2_am_on_fridays",
+			occurrences("every hour from 10pm to 2am on fridays")
+		);
+	}
+
+	#[test]
+	fn every_monday_at_10_am_lasting_1_hour() {
+		assert_debug_snapshot_matches!(
+			"every_monday_at_10_am_lasting_1_hour",
+			occurrences("every monday at 10am lasting 1 hour")
+		);
+	}
+
+	fn days(input: &str, from: &str) -> Vec<String> {
+		let (_, schedule) = parse_schedule(input, Tz::UTC, now()).unwrap();
+		schedule
+			.iter_within(Interval::from(from.parse().unwrap()))
+			.take(100)
+			.map(|interval| interval.from.date_naive().to_string())
+			.collect()
+	}
+
+	#[test]
+	fn fixed_periods_step_from_the_series_start() {
+		assert_eq!(
+			first(
+				"every 2 hours starting 2019-01-01T01:00",
+				"2018-12-31T00:00:00Z",
+				2
+			),
+			vec!["01-01 01:00..01-01 03:00", "01-01 03:00..01-01 05:00"]
+		);
+		assert_eq!(
+			first(
+				"every 2 hours starting 2019-01-01T01:00",
+				"2019-06-01T00:30:00Z",
+				1
+			),
+			vec!["06-01 01:00..06-01 03:00"]
+		);
+
+		let input = "every 10 minutes starting 1990-01-01 for 3 times";
+		assert_eq!(
+			first(input, "1980-01-01T00:00:00Z", 4),
+			vec![
+				"01-01 00:00..01-01 00:10",
+				"01-01 00:10..01-01 00:20",
+				"01-01 00:20..01-01 00:30"
+			]
+		);
+		assert!(first(input, "2019-01-01T00:00:00Z", 1).is_empty());
+	}
+
+	#[test]
+	fn count_from_the_series_start() {
+		let input = "every monday starting 2019-01-01 for 3 times";
+		assert_eq!(
+			days(input, "2018-12-01T00:00:00Z"),
+			vec!["2019-01-07", "2019-01-14", "2019-01-21"]
+		);
+		assert_eq!(
+			days(input, "2019-01-10T00:00:00Z"),
+			vec!["2019-01-14", "2019-01-21"]
+		);
+	}
+
+	#[test]
+	fn on_the_31st_or_roll_to_next_month() {
+		assert_eq!(
+			days(
+				"on the 31st, or roll to next month for 3 times",
+				"2019-04-01T00:00:00Z"
+			),
+			vec!["2019-05-01", "2019-05-31", "2019-07-01"]
+		);
+	}
+
+	#[test]
+	fn every_1st_starting_for_3_months() {
+		assert_eq!(
+			days(
+				"on the 1st starting 2019-02-01 for 3 months",
+				"2019-01-01T00:00:00Z"
+			),
+			vec!["2019-02-01", "2019-03-01", "2019-04-01"]
+		);
+	}
+
+	#[test]
+	fn except_and_also_on_dates() {
+		assert_eq!(
+			days(
+				"every monday at 9am until 2020-01-14 except on 2019-12-23 \
+				 and dec 30 also on 2019-12-28 and 2020-01-06T09:00",
+				"2019-12-10T00:00:00Z"
+			),
+			vec!["2019-12-16", "2019-12-28", "2020-01-06", "2020-01-13"]
+		);
+	}
+
+	#[test]
+	fn on_the_25th_or_the_next_business_day() {
+		assert_eq!(
+			days(
+				"on the 25th, or the next us business day at 9am for 3 times",
+				"2019-05-01T00:00:00Z"
+			),
+			// 2019-05-27 is memorial day
+			vec!["2019-05-28", "2019-06-25", "2019-07-25"]
+		);
+	}
+
+	#[test]
+	fn offsets_from_occurrences() {
+		assert_eq!(
+			days(
+				"3 us business days before the last day of the month at 5pm",
+				"2019-03-01T00:00:00Z"
+			)[..3],
+			["2019-03-27", "2019-04-25", "2019-05-28"]
+		);
+		assert_debug_snapshot_matches!(
+			"15_minutes_after_every_monday_at_10_am",
+			occurrences(
+				"15 minutes after every monday at 10am lasting 30 minutes"
+			)
+		);
+	}
+
+	#[test]
+	fn exclusions_before_offsets() {
+		assert_eq!(
+			days(
+				"1 day after every monday at 10am except on 2019-06-03 \
+				 for 2 times",
+				"2019-06-01T00:00:00Z"
+			),
+			vec!["2019-06-11"]
+		);
+	}
+
+	#[test]
+	fn every_weekday_except_public_holidays() {
+		let (_, schedule) = parse_schedule(
+			"every weekday except public holidays",
+			chrono_tz::America::New_York,
+			now(),
+		)
+		.unwrap();
+
+		let days: Vec<String> = schedule
+			.iter_within(Interval::from(
+				"2019-11-27T05:00:00Z".parse().unwrap(),
+			))
+			.take(3)
+			.map(|interval| interval.from.date_naive().to_string())
+			.collect();
+
+		// Thanksgiving is on the 4th thursday of november
+		assert_eq!(days, vec!["2019-11-27", "2019-11-29", "2019-12-02"]);
+	}
 }
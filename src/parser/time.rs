@@ -8,6 +8,7 @@ use nom::{
 };
 
 use crate::at::At;
+use crate::clock::{MinuteOfHour, SecondOfMinute};
 
 use super::error::{ParseError, ParseResult};
 use super::utils::parse_chain;
@@ -18,7 +19,7 @@ enum Abbr {
 	PM,
 }
 
-fn parse_at(input: &str) -> ParseResult<At> {
+pub fn parse_at(input: &str) -> ParseResult<'_, At> {
 	let (input, _) = tag("at")(input)?;
 	let (input, _) = space1(input)?;
 	let (input, times) = parse_chain(input, |input| {
@@ -29,7 +30,44 @@ fn parse_at(input: &str) -> ParseResult<At> {
 	Ok((input, At::new(times)))
 }
 
-pub fn parse_time(input: &str) -> ParseResult<NaiveTime> {
+/// Parse a minute or a second between 0 and 59
+fn parse_sixty(input: &str) -> ParseResult<'_, u32> {
+	let (input, value) = digit1(input)?;
+	let value: u32 = value
+		.parse()
+		.map_err(|e| ParseError::InvalidNumericValue(e).into_fail(input))?;
+
+	if value > 59 {
+		return Err(ParseError::OutOfRange.into_fail(input));
+	}
+
+	Ok((input, value))
+}
+
+/// Parse "at :00 and :30"
+pub fn parse_at_minutes(input: &str) -> ParseResult<'_, MinuteOfHour> {
+	let (input, _) = tag("at")(input)?;
+	let (input, _) = space1(input)?;
+	let (input, minutes) = parse_chain(input, |input| {
+		let (input, _) = tag(":")(input)?;
+		parse_sixty(input)
+	})?;
+
+	Ok((input, MinuteOfHour::new(minutes)))
+}
+
+/// Parse "at second 15" or "at seconds 0 and 30"
+pub fn parse_at_seconds(input: &str) -> ParseResult<'_, SecondOfMinute> {
+	let (input, _) = tag("at")(input)?;
+	let (input, _) = space1(input)?;
+	let (input, _) = alt((tag("seconds"), tag("second")))(input)?;
+	let (input, _) = space1(input)?;
+	let (input, seconds) = parse_chain(input, parse_sixty)?;
+
+	Ok((input, SecondOfMinute::new(seconds)))
+}
+
+pub fn parse_time(input: &str) -> ParseResult<'_, NaiveTime> {
 	let (input, h) = digit1(input)?;
 	let (input, m) = opt(tuple((tag(":"), digit1)))(input)?;
 	let (input, s) = opt(tuple((tag(":"), digit1)))(input)?;
@@ -53,42 +91,42 @@ pub fn parse_time(input: &str) -> ParseResult<NaiveTime> {
 		None => 0,
 	};
 
+	let invalid = |e| ParseError::InvalidTime(e).into_fail(input);
+
 	let mut parsed = Parsed::new();
-	parsed.set_minute(m);
-	parsed.set_second(s);
+	parsed.set_minute(m).map_err(invalid)?;
+	parsed.set_second(s).map_err(invalid)?;
 
 	match ampm {
 		Some((_, ampm)) => {
-			parsed.set_hour12(h);
-			parsed.set_ampm(if ampm == Abbr::AM { false } else { true });
+			parsed.set_hour12(h).map_err(invalid)?;
+			parsed.set_ampm(ampm == Abbr::PM).map_err(invalid)?;
 		}
 		None => {
-			parsed.set_hour(h);
+			parsed.set_hour(h).map_err(invalid)?;
 		}
 	}
 
-	let time = parsed
-		.to_naive_time()
-		.map_err(|e| ParseError::InvalidTime(e).into_fail(input))?;
+	let time = parsed.to_naive_time().map_err(invalid)?;
 
 	Ok((input, time))
 }
 
-fn parse_am(input: &str) -> ParseResult<Abbr> {
+fn parse_am(input: &str) -> ParseResult<'_, Abbr> {
 	let (input, _) =
 		alt((tag("AM"), tag("am"), tag("A.M"), tag("a.m.")))(input)?;
 
 	Ok((input, Abbr::AM))
 }
 
-fn parse_pm(input: &str) -> ParseResult<Abbr> {
+fn parse_pm(input: &str) -> ParseResult<'_, Abbr> {
 	let (input, _) =
 		alt((tag("PM"), tag("pm"), tag("P.M"), tag("p.m.")))(input)?;
 
 	Ok((input, Abbr::PM))
 }
 
-fn parse_abbr(input: &str) -> ParseResult<Abbr> {
+fn parse_abbr(input: &str) -> ParseResult<'_, Abbr> {
 	alt((parse_am, parse_pm))(input)
 }
 
@@ -99,7 +137,7 @@ mod tests {
 	fn parse_at_10_00() {
 		assert_eq!(
 			parse_at("at 10:00").unwrap().1,
-			At::new(vec![NaiveTime::from_hms(10, 0, 0)])
+			At::new(vec![NaiveTime::from_hms_opt(10, 0, 0).unwrap()])
 		)
 	}
 
@@ -107,7 +145,7 @@ mod tests {
 	fn parse_at_7_pm() {
 		assert_eq!(
 			parse_at("at 7 pm").unwrap().1,
-			At::new(vec![NaiveTime::from_hms(19, 0, 0)])
+			At::new(vec![NaiveTime::from_hms_opt(19, 0, 0).unwrap()])
 		)
 	}
 
@@ -116,10 +154,23 @@ mod tests {
 		assert_eq!(
 			parse_at("at 7pm, 8:30pm and 20:30").unwrap().1,
 			At::new(vec![
-				NaiveTime::from_hms(19, 0, 0),
-				NaiveTime::from_hms(20, 30, 0),
-				NaiveTime::from_hms(20, 30, 0)
+				NaiveTime::from_hms_opt(19, 0, 0).unwrap(),
+				NaiveTime::from_hms_opt(20, 30, 0).unwrap(),
+				NaiveTime::from_hms_opt(20, 30, 0).unwrap()
 			])
 		)
 	}
+
+	#[test]
+	fn parse_at_minutes_and_seconds() {
+		assert_eq!(
+			parse_at_minutes("at :00 and :30").unwrap(),
+			("", MinuteOfHour::new(vec![0, 30]))
+		);
+		assert_eq!(
+			parse_at_seconds("at seconds 15, 45").unwrap(),
+			("", SecondOfMinute::new(vec![15, 45]))
+		);
+		assert!(parse_at_minutes("at :60").is_err());
+	}
 }
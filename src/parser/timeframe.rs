@@ -0,0 +1,82 @@
+use chrono::{NaiveTime, Weekday};
+use nom::{
+	branch::alt, bytes::complete::tag, character::complete::space1,
+	sequence::tuple,
+};
+
+use crate::timeframe::{TimeFrame, WeekFrame};
+
+use super::error::ParseResult;
+use super::time::parse_time;
+use super::weekday::parse_day_of_week;
+
+/// Parse "from <a> to <b>" or "between <a> and <b>"
+pub fn parse_range<'a, T>(
+	input: &'a str,
+	parser: impl Fn(&'a str) -> ParseResult<'a, T>,
+) -> ParseResult<'a, (T, T)> {
+	let (input, (start, _)) = alt((
+		tuple((tag("from"), space1)),
+		tuple((tag("between"), space1)),
+	))(input)?;
+	let (input, from) = parser(input)?;
+	let (input, _) = space1(input)?;
+	let (input, _) = tag(if start == "from" { "to" } else { "and" })(input)?;
+	let (input, _) = space1(input)?;
+	let (input, to) = parser(input)?;
+
+	Ok((input, (from, to)))
+}
+
+pub fn parse_timeframe(input: &str) -> ParseResult<'_, TimeFrame> {
+	let (input, (from, to)) = parse_range(input, parse_time)?;
+	Ok((input, TimeFrame::new(from, to)))
+}
+
+fn parse_day_time(input: &str) -> ParseResult<'_, (Weekday, NaiveTime)> {
+	let (input, day) = parse_day_of_week(input)?;
+	let (input, _) = space1(input)?;
+	let (input, time) = parse_time(input)?;
+	Ok((input, (day, time)))
+}
+
+pub fn parse_weekframe(input: &str) -> ParseResult<'_, WeekFrame> {
+	let (input, (from, to)) = parse_range(input, parse_day_time)?;
+	Ok((input, WeekFrame::new(from, to)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn hm(h: u32, m: u32) -> NaiveTime {
+		NaiveTime::from_hms_opt(h, m, 0).unwrap()
+	}
+
+	#[test]
+	fn timeframe() {
+		assert_eq!(
+			parse_timeframe("from 9:00 to 17:30").unwrap(),
+			("", TimeFrame::new(hm(9, 0), hm(17, 30)))
+		);
+		assert_eq!(
+			parse_timeframe("between 11pm and 1am").unwrap(),
+			("", TimeFrame::new(hm(23, 0), hm(1, 0)))
+		);
+		assert!(parse_timeframe("between 11pm to 1am").is_err());
+	}
+
+	#[test]
+	fn weekframe() {
+		assert_eq!(
+			parse_weekframe("from fri 18:00 to mon 08:00").unwrap(),
+			(
+				"",
+				WeekFrame::new(
+					(Weekday::Fri, hm(18, 0)),
+					(Weekday::Mon, hm(8, 0))
+				)
+			)
+		);
+	}
+}
@@ -8,9 +8,9 @@ use nom::{
 
 use super::error::ParseResult;
 
-pub fn parse_chain<T, F>(input: &str, parser: F) -> ParseResult<Vec<T>>
+pub fn parse_chain<T, F>(input: &str, parser: F) -> ParseResult<'_, Vec<T>>
 where
-	F: Fn(&str) -> ParseResult<T>,
+	F: Fn(&str) -> ParseResult<'_, T>,
 {
 	let mut res = vec![];
 	let sep = opt(alt((
@@ -23,13 +23,19 @@ where
 
 	loop {
 		let (i, s) = sep(input)?;
-		match s {
-			Some(_) => {
-				let (i, value) = parser(i)?;
+		if s.is_none() {
+			break;
+		}
+
+		// A separator not followed by a value belongs to what comes next,
+		// like the comma in "on the 25th, or the next business day"
+		match parser(i) {
+			Ok((i, value)) => {
 				res.push(value);
 				input = i
 			}
-			None => break,
+			Err(nom::Err::Error(_)) => break,
+			Err(e) => return Err(e),
 		}
 	}
 
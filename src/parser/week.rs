@@ -0,0 +1,103 @@
+use chrono::Weekday;
+use nom::{
+	branch::alt,
+	bytes::complete::tag,
+	character::complete::{digit1, space1},
+	combinator::{map, opt},
+	sequence::tuple,
+};
+
+use crate::every::Every;
+use crate::period::Period;
+use crate::week::Parity;
+
+use super::error::{ParseError, ParseResult};
+use super::utils::parse_chain;
+use super::weekday::parse_day_of_week;
+
+fn parse_parity(input: &str) -> ParseResult<'_, Parity> {
+	let (input, parity) = alt((
+		map(tag("even"), |_| Parity::Even),
+		map(tag("odd"), |_| Parity::Odd),
+	))(input)?;
+	let (input, _) = space1(input)?;
+	let (input, _) = alt((tag("weeks"), tag("week")))(input)?;
+	Ok((input, parity))
+}
+
+fn parse_week_number(input: &str) -> ParseResult<'_, u32> {
+	let (input, _) = tag("week")(input)?;
+	let (input, _) = space1(input)?;
+	let (input, week) = digit1(input)?;
+	let week: u32 = week
+		.parse()
+		.map_err(|e| ParseError::InvalidNumericValue(e).into_fail(input))?;
+
+	if week == 0 || week > 53 {
+		return Err(ParseError::InvalidDate.into_fail(input));
+	}
+
+	Ok((input, week))
+}
+
+/// Parse " starting on sunday" and similar
+fn parse_week_start(input: &str) -> ParseResult<'_, Weekday> {
+	let (input, _) = space1(input)?;
+	let (input, _) = tag("starting")(input)?;
+	let (input, _) = space1(input)?;
+	let (input, _) = opt(tuple((tag("on"), space1)))(input)?;
+	parse_day_of_week(input)
+}
+
+/// Parse "even weeks", "odd week" or "week 10", weeks start on monday
+/// unless followed by "starting on <weekday>"
+pub fn parse_week(input: &str) -> ParseResult<'_, Period> {
+	enum Week {
+		Parity(Parity),
+		Number(u32),
+	}
+
+	let (input, week) = alt((
+		map(parse_parity, Week::Parity),
+		map(parse_week_number, Week::Number),
+	))(input)?;
+	let (input, start) = opt(parse_week_start)(input)?;
+	let start = start.unwrap_or(Weekday::Mon);
+
+	let period = match week {
+		Week::Parity(parity) => Period::WeekParity(parity, start),
+		Week::Number(week) => Period::WeekOfYear(week, start),
+	};
+
+	Ok((input, period))
+}
+
+/// Parse "in week 10", "in week 10 and week 20" or "in even weeks"
+pub fn parse_in_week(input: &str) -> ParseResult<'_, Every> {
+	let (input, _) = tag("in")(input)?;
+	let (input, _) = space1(input)?;
+	let (input, weeks) = parse_chain(input, parse_week)?;
+	Ok((input, Every::new(weeks)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_weeks() {
+		assert_eq!(
+			parse_week("even weeks").unwrap(),
+			("", Period::WeekParity(Parity::Even, Weekday::Mon))
+		);
+		assert_eq!(
+			parse_week("odd week starting on sunday").unwrap(),
+			("", Period::WeekParity(Parity::Odd, Weekday::Sun))
+		);
+		assert_eq!(
+			parse_in_week("in week 10").unwrap(),
+			("", Every::new(vec![Period::WeekOfYear(10, Weekday::Mon)]))
+		);
+		assert!(parse_week("week 54").is_err());
+	}
+}
@@ -1,21 +1,58 @@
 use chrono::Weekday;
-use nom::character::complete::alpha1;
+use nom::{
+	bytes::complete::tag,
+	character::complete::{alpha1, space1},
+};
 
 use super::error::{ParseError, ParseResult};
+use super::utils::parse_chain;
+use crate::every::Every;
 use crate::period::Period;
+use crate::weekday::Weekend;
 
-pub fn parse_weekday(input: &str) -> ParseResult<Period> {
+pub fn parse_weekday(input: &str) -> ParseResult<'_, Period> {
 	let (input, dim) = alpha1(input)?;
+	// "on mondays" and "on weekdays" use plurals
+	let dim = match dim.strip_suffix('s') {
+		Some(single) if single.len() > 2 => single,
+		_ => dim,
+	};
+
 	match dim {
-		"weekday" => Ok((input, Period::Weekday)),
-		"weekend" => Ok((input, Period::Weekend)),
-		"monday" | "mon" => Ok((input, Period::DayOfWeek(Weekday::Mon))),
-		"tuesday" | "tue" => Ok((input, Period::DayOfWeek(Weekday::Tue))),
-		"wednesday" | "wed" => Ok((input, Period::DayOfWeek(Weekday::Wed))),
-		"thursday" | "thu" => Ok((input, Period::DayOfWeek(Weekday::Thu))),
-		"friday" | "fri" => Ok((input, Period::DayOfWeek(Weekday::Fri))),
-		"saturday" | "sat" => Ok((input, Period::DayOfWeek(Weekday::Sat))),
-		"sunday" | "sun" => Ok((input, Period::DayOfWeek(Weekday::Sun))),
-		_ => Err(ParseError::Unsupported.into_err(input)),
+		"weekday" => Ok((input, Period::Weekday(Weekend::default()))),
+		"weekend" => Ok((input, Period::Weekend(Weekend::default()))),
+		_ => match day_of_week(dim) {
+			Some(day) => Ok((input, Period::DayOfWeek(day))),
+			None => Err(ParseError::Unsupported.into_err(input)),
+		},
+	}
+}
+
+/// Parse "on weekdays" or "on mondays and fridays"
+pub fn parse_on_weekdays(input: &str) -> ParseResult<'_, Every> {
+	let (input, _) = tag("on")(input)?;
+	let (input, _) = space1(input)?;
+	let (input, days) = parse_chain(input, parse_weekday)?;
+	Ok((input, Every::new(days)))
+}
+
+pub fn parse_day_of_week(input: &str) -> ParseResult<'_, Weekday> {
+	let (input, dim) = alpha1(input)?;
+	match day_of_week(dim) {
+		Some(day) => Ok((input, day)),
+		None => Err(ParseError::Unsupported.into_err(input)),
+	}
+}
+
+fn day_of_week(name: &str) -> Option<Weekday> {
+	match name {
+		"monday" | "mon" => Some(Weekday::Mon),
+		"tuesday" | "tue" => Some(Weekday::Tue),
+		"wednesday" | "wed" => Some(Weekday::Wed),
+		"thursday" | "thu" => Some(Weekday::Thu),
+		"friday" | "fri" => Some(Weekday::Fri),
+		"saturday" | "sat" => Some(Weekday::Sat),
+		"sunday" | "sun" => Some(Weekday::Sun),
+		_ => None,
 	}
 }
@@ -1,32 +1,71 @@
 use crate::interval::{Interval, Timeline};
-use chrono::{Duration, Weekday};
+use chrono::{DateTime, Duration, Month, Weekday};
 
+use crate::day::{DayIterator, ShortMonth};
+use crate::duration::{iter_anchored, nanos, Anchor};
 use crate::merge::MergeN;
-use crate::month::MonthIterator;
+use crate::month::{
+	run_start, Align, AlignedMonthsIterator, MonthIterator, MonthRangeIterator,
+};
+use crate::types::Dimension;
+use crate::utils::Of;
+use crate::week::{Parity, WeekIterator};
+use crate::weekday::Weekend;
+use crate::zone::Zone;
 
 #[derive(Debug, PartialEq)]
 pub enum Period {
-	Fixed(Duration),
+	/// Steps of a fixed duration that begin at the anchor
+	Fixed(Duration, Anchor),
 	Month(i32),
-	Quarter(i32),
-	Year(i32),
-	Weekend,
-	Weekday,
+	Quarter(i32, Align),
+	Year(i32, Align),
+	Weekend(Weekend),
+	Weekday(Weekend),
 	DayOfWeek(Weekday),
 	Ordinal(usize, Box<Period>),
+	/// The nth weekday of every month, quarter or year, negative positions
+	/// count from the end so `-1` is the last one
+	NthWeekday(i32, Weekday, Of),
+	/// The nth day of every month, negative days count from the end so `-1`
+	/// is the last day of the month
+	DayOfMonth(i32, ShortMonth),
+	/// The same month of every year
+	MonthOfYear(Month),
+	/// The months from the first to the second one inclusive, repeated every
+	/// year, the range wraps around the end of the year
+	Months(Month, Month),
+	/// The week with the given ISO 8601 number in every year, weeks start on
+	/// the given day
+	WeekOfYear(u32, Weekday),
+	/// Every even or odd week by its ISO 8601 number, weeks start on the
+	/// given day
+	WeekParity(Parity, Weekday),
 }
 
 impl Timeline for Period {
 	fn duration_hint(&self) -> Duration {
 		match self {
-			Period::Fixed(d) => d.clone(),
+			Period::Fixed(d, _) => *d,
 			Period::Month(n) => Duration::weeks(4) * *n,
-			Period::Quarter(n) => Duration::weeks(4) * 3 * *n,
-			Period::Year(n) => Duration::weeks(4) * 12 * *n,
-			Period::Weekday | Period::Weekend | Period::DayOfWeek(_) => {
-				Duration::days(1)
-			}
+			Period::Quarter(n, _) => Duration::weeks(4) * 3 * *n,
+			Period::Year(n, _) => Duration::weeks(4) * 12 * *n,
+			Period::Weekday(_)
+			| Period::Weekend(_)
+			| Period::DayOfWeek(_)
+			| Period::NthWeekday(..)
+			| Period::DayOfMonth(..) => Duration::days(1),
 			Period::Ordinal(_, p) => p.duration_hint(),
+			Period::MonthOfYear(_) => Duration::weeks(4),
+			Period::WeekOfYear(..) | Period::WeekParity(..) => {
+				Duration::weeks(1)
+			}
+			Period::Months(from, to) => {
+				let months = (12 + to.number_from_month()
+					- from.number_from_month())
+					% 12 + 1;
+				Duration::weeks(4) * months as i32
+			}
 		}
 	}
 
@@ -35,24 +74,103 @@ impl Timeline for Period {
 		interval: Interval,
 	) -> Box<dyn Iterator<Item = Interval>> {
 		match self {
-			Period::Fixed(d) => d.iter_within(interval),
+			Period::Fixed(d, anchor) => iter_anchored(*d, *anchor, interval),
 			Period::Month(m) => {
 				Box::new(MergeN::new(MonthIterator::new(interval), *m as usize))
 			}
-			Period::Quarter(q) => Box::new(MergeN::new(
-				MonthIterator::new(interval),
-				(q * 3) as usize,
-			)),
-			Period::Year(y) => Box::new(MergeN::new(
-				MonthIterator::new(interval),
-				(y * 12) as usize,
-			)),
+			Period::Quarter(q, align) => months(q * 3, *align, interval),
+			Period::Year(y, align) => months(y * 12, *align, interval),
 			Period::DayOfWeek(w) => Box::new(w.iter_within(interval)),
-			Period::Weekday => unimplemented!(),
-			Period::Weekend => unimplemented!(),
+			Period::Weekday(weekend) => weekend.weekdays(interval),
+			Period::Weekend(weekend) => weekend.weekends(interval),
 			Period::Ordinal(m, p) => {
 				Box::new(p.iter_within(interval).step_by(*m))
 			}
+			Period::NthWeekday(nth, weekday, of) => Box::new(
+				DayIterator::nth_weekday(*nth, *weekday, *of, interval),
+			),
+			Period::DayOfMonth(nth, short) => {
+				Box::new(DayIterator::day_of_month(*nth, *short, interval))
+			}
+			Period::MonthOfYear(month) => {
+				Box::new(MonthRangeIterator::new(*month, *month, interval))
+			}
+			Period::Months(from, to) => {
+				Box::new(MonthRangeIterator::new(*from, *to, interval))
+			}
+			Period::WeekOfYear(week, start) => {
+				Box::new(WeekIterator::number(*week, *start, interval))
+			}
+			Period::WeekParity(parity, start) => {
+				Box::new(WeekIterator::parity(*parity, *start, interval))
+			}
+		}
+	}
+
+	fn cycle(&self) -> Option<Dimension> {
+		match self {
+			Period::Weekday(_) | Period::Weekend(_) | Period::DayOfWeek(_) => {
+				Some(Dimension::Week)
+			}
+			Period::NthWeekday(_, _, of) => Some(match of {
+				Of::Day => Dimension::Day,
+				Of::Month => Dimension::Month,
+				Of::Quarter => Dimension::Quarter,
+				Of::Year => Dimension::Year,
+			}),
+			Period::DayOfMonth(..) => Some(Dimension::Month),
+			Period::MonthOfYear(_)
+			| Period::Months(..)
+			| Period::WeekOfYear(..) => Some(Dimension::Year),
+			_ => None,
+		}
+	}
+
+	fn step(&self, interval: &Interval) -> Option<(DateTime<Zone>, Dimension)> {
+		// Calendar aligned runs of months are cut at the start of the
+		// iteration, the others begin where their steps do
+		let aligned = |months, align| match align {
+			Align::Calendar(start) => run_start(months, start, &interval.from),
+			Align::Rolling => Some(interval.from),
+		};
+
+		match self {
+			Period::Fixed(duration, _) => {
+				let unit = [
+					(Duration::weeks(1), Dimension::Week),
+					(Duration::days(1), Dimension::Day),
+					(Duration::hours(1), Dimension::Hour),
+					(Duration::minutes(1), Dimension::Minute),
+					(Duration::seconds(1), Dimension::Second),
+				]
+				.iter()
+				.find(|(unit, _)| nanos(*duration) % nanos(*unit) == 0)
+				.map(|(_, dimension)| *dimension)?;
+				Some((interval.from, unit))
+			}
+			Period::Month(_) => Some((interval.from, Dimension::Month)),
+			Period::Quarter(n, align) => {
+				Some((aligned(n * 3, *align)?, Dimension::Month))
+			}
+			Period::Year(n, align) => {
+				Some((aligned(n * 12, *align)?, Dimension::Year))
+			}
+			_ => None,
+		}
+	}
+}
+
+fn months(
+	n: i32,
+	align: Align,
+	interval: Interval,
+) -> Box<dyn Iterator<Item = Interval>> {
+	match align {
+		Align::Calendar(start) => {
+			Box::new(AlignedMonthsIterator::new(n, start, interval))
+		}
+		Align::Rolling => {
+			Box::new(MergeN::new(MonthIterator::new(interval), n as usize))
 		}
 	}
 }
@@ -77,4 +195,161 @@ mod tests {
 				.collect::<Vec<_>>()
 		);
 	}
+
+	#[test]
+	fn every_weekday() {
+		let every_weekday = Period::Weekday(Weekend::default());
+
+		assert_debug_snapshot_matches!(
+			"every_weekday",
+			every_weekday
+				.iter_within(Interval::from(
+					"2019-10-31T12:00:00Z".parse().unwrap(),
+				))
+				.take(6)
+				.collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn every_fri_sat_weekend() {
+		let every_weekend = Period::Weekend(Weekend::fri_sat());
+
+		assert_debug_snapshot_matches!(
+			"every_fri_sat_weekend",
+			every_weekend
+				.iter_within(Interval::from(
+					"2019-10-31T12:00:00Z".parse().unwrap(),
+				))
+				.take(3)
+				.collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn last_sunday_of_the_month() {
+		let last_sunday = Period::NthWeekday(-1, Weekday::Sun, Of::Month);
+
+		assert_debug_snapshot_matches!(
+			"last_sunday_of_the_month",
+			last_sunday
+				.iter_within(Interval::from(
+					"2019-10-31T12:00:00Z".parse().unwrap(),
+				))
+				.take(3)
+				.collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn fifth_friday_of_the_month() {
+		let fifth_friday = Period::NthWeekday(5, Weekday::Fri, Of::Month);
+
+		let days: Vec<_> = fifth_friday
+			.iter_within(
+				Interval::from("2019-01-01T00:00:00Z".parse().unwrap())
+					.until("2020-01-01T00:00:00Z".parse().unwrap()),
+			)
+			.map(|interval| interval.from.date_naive().to_string())
+			.collect();
+
+		assert_eq!(
+			days,
+			vec!["2019-03-29", "2019-05-31", "2019-08-30", "2019-11-29"]
+		);
+	}
+
+	#[test]
+	fn the_31st_in_short_months() {
+		let days = |short| {
+			Period::DayOfMonth(31, short)
+				.iter_within(
+					Interval::from("2019-01-01T00:00:00Z".parse().unwrap())
+						.until("2019-05-02T00:00:00Z".parse().unwrap()),
+				)
+				.map(|interval| interval.from.date_naive().to_string())
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(days(ShortMonth::Skip), vec!["2019-01-31", "2019-03-31"]);
+		assert_eq!(
+			days(ShortMonth::Clamp),
+			vec!["2019-01-31", "2019-02-28", "2019-03-31", "2019-04-30"]
+		);
+		assert_eq!(
+			days(ShortMonth::Roll),
+			vec!["2019-01-31", "2019-03-01", "2019-03-31", "2019-05-01"]
+		);
+	}
+
+	#[test]
+	fn from_november_to_february() {
+		let winter = Period::Months(Month::November, Month::February);
+
+		assert_debug_snapshot_matches!(
+			"from_november_to_february",
+			winter
+				.iter_within(Interval::from(
+					"2019-01-15T00:00:00Z".parse().unwrap(),
+				))
+				.take(3)
+				.collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn every_fiscal_quarter() {
+		let quarter = Period::Quarter(1, Align::Calendar(Month::April));
+
+		assert_debug_snapshot_matches!(
+			"every_fiscal_quarter",
+			quarter
+				.iter_within(Interval::from(
+					"2019-02-10T12:00:00Z".parse().unwrap(),
+				))
+				.take(3)
+				.collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn years_past_the_last_date() {
+		let years = Period::Year(200_000, Align::default())
+			.iter_within(Interval::from(
+				"2019-01-01T00:00:00Z".parse().unwrap(),
+			))
+			.count();
+
+		// The run after 200000 ends past the range of dates
+		assert_eq!(years, 1);
+	}
+
+	#[test]
+	fn every_odd_week_around_week_53() {
+		let odd = Period::WeekParity(Parity::Odd, Weekday::Mon);
+
+		let weeks: Vec<_> = odd
+			.iter_within(Interval::from(
+				"2020-12-14T00:00:00Z".parse().unwrap(),
+			))
+			.take(3)
+			.map(|interval| interval.from.date_naive().to_string())
+			.collect();
+
+		// 2020 has 53 weeks, so two odd weeks follow each other
+		assert_eq!(weeks, vec!["2020-12-14", "2020-12-28", "2021-01-04"]);
+	}
+
+	#[test]
+	fn week_10_starting_sunday() {
+		let week = Period::WeekOfYear(10, Weekday::Sun)
+			.iter_within(Interval::from(
+				"2019-01-01T00:00:00Z".parse().unwrap(),
+			))
+			.next()
+			.unwrap();
+
+		assert_eq!(week.from.date_naive().to_string(), "2019-03-03");
+		assert_eq!(week.to.unwrap().date_naive().to_string(), "2019-03-10");
+	}
 }
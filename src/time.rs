@@ -1,8 +1,8 @@
-use chrono::Timelike;
 use chrono::{Duration, NaiveTime};
 
+use crate::at::AtIterator;
 use crate::interval::{Interval, Timeline};
-use crate::utils::{end_of, start_of, Of};
+use crate::types::Dimension;
 
 impl Timeline for NaiveTime {
 	fn duration_hint(&self) -> Duration {
@@ -13,58 +13,11 @@ impl Timeline for NaiveTime {
 		&self,
 		interval: Interval,
 	) -> Box<dyn Iterator<Item = Interval>> {
-		Box::new(TimeIterator {
-			time: self.clone(),
-			interval,
-			completed: false,
-		})
+		Box::new(AtIterator::new(vec![*self], interval))
 	}
-}
-
-struct TimeIterator {
-	time: NaiveTime,
-	interval: Interval,
-	completed: bool,
-}
-
-impl Iterator for TimeIterator {
-	type Item = Interval;
-
-	fn next(&mut self) -> Option<Interval> {
-		if self.completed {
-			return None;
-		}
-
-		let mut from = self.interval.from.clone();
-		let from_time = from.time();
-
-		if from_time > self.time {
-			// Jump to the next day
-			from = start_of(
-				Of::Day,
-				&(end_of(Of::Day, &from) + Duration::seconds(1)),
-			);
-		}
-
-		let datetime = from
-			.with_hour(self.time.hour())
-			.and_then(|time| time.with_minute(self.time.minute()))
-      .and_then(|time| time.with_second(self.time.second()))
-			.unwrap();
-
-		if self.interval.to.is_some()
-			&& datetime > *self.interval.to.as_ref().unwrap()
-		{
-			self.completed = true;
-			return None;
-		}
-
-		self.interval.from = datetime + Duration::seconds(1);
 
-		Some(Interval {
-			from: datetime,
-			to: Some(datetime),
-		})
+	fn cycle(&self) -> Option<Dimension> {
+		Some(Dimension::Day)
 	}
 }
 
@@ -76,7 +29,7 @@ mod tests {
 
 	#[test]
 	fn every_10_am() {
-		let am_10 = NaiveTime::from_hms(10, 0, 0);
+		let am_10 = NaiveTime::from_hms_opt(10, 0, 0).unwrap();
 
 		assert_debug_snapshot_matches!(
 			"every_10_am",
@@ -1,18 +1,22 @@
-use chrono::{Duration, NaiveTime, Timelike};
+use std::collections::VecDeque;
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
 
 use crate::interval::{Interval, Timeline};
-use crate::utils::{end_of, start_of, Of};
+use crate::types::Dimension;
 
-#[derive(Debug, Clone)]
-struct TimeFrame {
+/// A window of time repeated every day, the window ends on the next day
+/// when `to` is not after `from`, so equal times make a whole day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeFrame {
 	pub from: NaiveTime,
 	pub to: NaiveTime,
 }
 
-struct TimeFrameIterator {
-	frame: TimeFrame,
-	interval: Interval,
-	completed: bool,
+impl TimeFrame {
+	pub fn new(from: NaiveTime, to: NaiveTime) -> Self {
+		TimeFrame { from, to }
+	}
 }
 
 impl Timeline for TimeFrame {
@@ -20,11 +24,8 @@ impl Timeline for TimeFrame {
 		if self.to > self.from {
 			self.to - self.from
 		} else {
-			Duration::days(1)
-				- self
-					.from
-					.signed_duration_since(NaiveTime::from_hms(0, 0, 0))
-				+ self.to.signed_duration_since(NaiveTime::from_hms(0, 0, 0))
+			Duration::days(1) - self.from.signed_duration_since(NaiveTime::MIN)
+				+ self.to.signed_duration_since(NaiveTime::MIN)
 		}
 	}
 
@@ -32,69 +33,158 @@ impl Timeline for TimeFrame {
 		&self,
 		interval: Interval,
 	) -> Box<dyn Iterator<Item = Interval>> {
-		Box::new(TimeFrameIterator {
-			frame: self.clone(),
+		// The window ends on the next day when `to` is not after `from`, so
+		// the one in progress can have started on the day before
+		let days = i64::from(self.to <= self.from);
+		let day = interval.from.date_naive() - Duration::days(days);
+		Box::new(WindowIterator::new(
+			(self.from, self.to),
+			days,
+			1,
+			day,
+			interval,
+		))
+	}
+
+	fn cycle(&self) -> Option<Dimension> {
+		Some(Dimension::Day)
+	}
+}
+
+/// Iterates over windows between two wall clock times repeated every
+/// `step` days, ending `days` after they start. Windows overlapping the
+/// interval are produced whole, so windows spanning midnight are not cut at
+/// the end of the day and steps within the one in progress stay aligned to
+/// its start.
+struct WindowIterator {
+	from: NaiveTime,
+	to: NaiveTime,
+	days: i64,
+	step: i64,
+	day: NaiveDate,
+	interval: Interval,
+	pending: VecDeque<Interval>,
+	completed: bool,
+}
+
+impl WindowIterator {
+	fn new(
+		(from, to): (NaiveTime, NaiveTime),
+		days: i64,
+		step: i64,
+		day: NaiveDate,
+		interval: Interval,
+	) -> Self {
+		WindowIterator {
+			from,
+			to,
+			days,
+			step,
+			day,
 			interval,
+			pending: VecDeque::new(),
 			completed: false,
-		})
+		}
 	}
 }
 
-impl Iterator for TimeFrameIterator {
+impl Iterator for WindowIterator {
 	type Item = Interval;
 
 	fn next(&mut self) -> Option<Interval> {
-		if self.completed {
-			return None;
-		}
-
-		let mut from = self.interval.from.clone();
-		if from.time() > self.frame.from {
-			// Jump to the next day
-			from = start_of(
-				Of::Day,
-				&(end_of(Of::Day, &from) + Duration::seconds(1)),
+		loop {
+			if self.completed {
+				return None;
+			}
+
+			if let Some(window) = self.pending.pop_front() {
+				if self.interval.to.is_some_and(|to| window.from >= to) {
+					self.completed = true;
+					return None;
+				}
+				return Some(window);
+			}
+
+			let zone = self.interval.from.timezone();
+			let day = self.day;
+			let start = zone.localize(&day.and_time(self.from));
+			if self.interval.to.is_some_and(|to| start >= to) {
+				self.completed = true;
+				return None;
+			}
+
+			// The policy of the zone decides whether a window starting at a
+			// skipped or repeated time happens once, twice or not at all
+			let since = Interval {
+				from: self.interval.from,
+				to: None,
+			};
+			let end = day + Duration::days(self.days);
+			self.pending.extend(
+				zone.windows(&day.and_time(self.from), &end.and_time(self.to))
+					.into_iter()
+					.filter(|window| !window.ends_before(&since)),
 			);
+			self.day = day + Duration::days(self.step);
 		}
+	}
+}
 
-		from = from
-			.with_hour(self.frame.from.hour())
-			.and_then(|time| time.with_minute(self.frame.from.minute()))
-			.and_then(|time| time.with_second(self.frame.from.second()))
-			.unwrap();
-
-		if self.interval.to.is_some()
-			&& from > *self.interval.to.as_ref().unwrap()
-		{
-			self.completed = true;
-			return None;
-		}
+/// A window of time repeated every week that can span multiple days, like
+/// from friday 18:00 to monday 08:00.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekFrame {
+	pub from: (Weekday, NaiveTime),
+	pub to: (Weekday, NaiveTime),
+}
 
-		let mut to = from;
-		if to.time() > self.frame.to {
-			// Jump to the next day
-			to = start_of(
-				Of::Day,
-				&(end_of(Of::Day, &from) + Duration::seconds(1)),
-			);
-		}
+impl WeekFrame {
+	pub fn new(from: (Weekday, NaiveTime), to: (Weekday, NaiveTime)) -> Self {
+		WeekFrame { from, to }
+	}
 
-		to = to
-			.with_hour(self.frame.to.hour())
-			.and_then(|time| time.with_minute(self.frame.to.minute()))
-      .and_then(|time| time.with_second(self.frame.to.second()))
-			.unwrap();
+	/// Number of days between the start and the end of the window
+	fn days(&self) -> i64 {
+		let days = (7 + self.to.0.num_days_from_monday()
+			- self.from.0.num_days_from_monday())
+			% 7;
 
-		if self.interval.to.is_some()
-			&& to > *self.interval.to.as_ref().unwrap()
-		{
-			self.completed = true;
-			return None;
+		if days == 0 && self.to.1 <= self.from.1 {
+			7
+		} else {
+			i64::from(days)
 		}
+	}
+}
 
-		self.interval.from = to + Duration::seconds(1);
+impl Timeline for WeekFrame {
+	fn duration_hint(&self) -> Duration {
+		Duration::days(self.days())
+			+ self.to.1.signed_duration_since(self.from.1)
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		// The window in progress can have started up to a week before
+		let date = interval.from.date_naive() - Duration::days(self.days());
+		let ahead = (7 + self.from.0.num_days_from_monday()
+			- date.weekday().num_days_from_monday())
+			% 7;
+		let day = date + Duration::days(i64::from(ahead));
+
+		Box::new(WindowIterator::new(
+			(self.from.1, self.to.1),
+			self.days(),
+			7,
+			day,
+			interval,
+		))
+	}
 
-		Some(Interval { from, to: Some(to) })
+	fn cycle(&self) -> Option<Dimension> {
+		Some(Dimension::Week)
 	}
 }
 
@@ -107,8 +197,8 @@ mod tests {
 	#[test]
 	fn from_23_00_to_1_00() {
 		let frame = TimeFrame {
-			from: NaiveTime::from_hms(23, 0, 0),
-			to: NaiveTime::from_hms(1, 0, 0),
+			from: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+			to: NaiveTime::from_hms_opt(1, 0, 0).unwrap(),
 		};
 
 		assert_debug_snapshot_matches!(
@@ -121,4 +211,37 @@ mod tests {
 				.collect::<Vec<_>>()
 		);
 	}
+
+	#[test]
+	fn from_9_00_to_9_00() {
+		let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+		let frame = TimeFrame::new(nine, nine);
+		let first = frame
+			.iter_within(Interval::from(
+				"2019-10-31T00:00:00Z".parse().unwrap(),
+			))
+			.next()
+			.unwrap();
+
+		assert_eq!(frame.duration_hint(), Duration::days(1));
+		assert_eq!(first.to.unwrap() - first.from, Duration::days(1));
+	}
+
+	#[test]
+	fn from_fri_18_00_to_mon_08_00() {
+		let frame = WeekFrame::new(
+			(Weekday::Fri, NaiveTime::from_hms_opt(18, 0, 0).unwrap()),
+			(Weekday::Mon, NaiveTime::from_hms_opt(8, 0, 0).unwrap()),
+		);
+
+		assert_debug_snapshot_matches!(
+			"from_fri_18_00_to_mon_08_00",
+			frame
+				.iter_within(Interval::from(
+					"2019-10-31T00:00:00Z".parse().unwrap(),
+				))
+				.take(2)
+				.collect::<Vec<_>>()
+		);
+	}
 }
@@ -1,6 +1,5 @@
-use chrono::{Duration, NaiveTime, Weekday};
-
-#[derive(Debug)]
+/// Units of time, in order of length
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Dimension {
 	Second,
 	Minute,
@@ -11,4 +10,3 @@ pub enum Dimension {
 	Quarter,
 	Year,
 }
-
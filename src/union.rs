@@ -0,0 +1,81 @@
+use chrono::Duration;
+
+use crate::combine::Combine;
+use crate::interval::{Interval, Timeline};
+use crate::merge::Merge;
+
+/// Time covered by any of the timelines, overlapping and adjacent intervals
+/// are merged together.
+#[derive(Debug)]
+pub struct Union(Vec<Box<dyn Timeline>>);
+
+impl Union {
+	pub fn new(timelines: Vec<Box<dyn Timeline>>) -> Self {
+		Union(timelines)
+	}
+}
+
+impl Timeline for Union {
+	fn duration_hint(&self) -> Duration {
+		self.0
+			.iter()
+			.map(|t| t.duration_hint())
+			.min()
+			.unwrap_or_else(Duration::zero)
+	}
+
+	fn iter_within(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		Box::new(Merge::new(
+			Combine::new(
+				self.0
+					.iter()
+					.map(|t| t.iter_within(interval.clone()))
+					.collect(),
+			),
+			usize::MAX,
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::timeframe::TimeFrame;
+	use chrono::NaiveTime;
+	use insta::assert_debug_snapshot_matches;
+
+	#[test]
+	fn empty() {
+		let union = Union::new(vec![]);
+		assert_eq!(union.duration_hint(), Duration::zero());
+		assert!(union
+			.iter_within(Interval::from(
+				"2019-01-01T00:00:00Z".parse().unwrap()
+			))
+			.next()
+			.is_none());
+	}
+
+	#[test]
+	fn morning_or_noon() {
+		let hm = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+		let union = Union::new(vec![
+			Box::new(TimeFrame::new(hm(9, 0), hm(12, 0))),
+			Box::new(TimeFrame::new(hm(11, 0), hm(13, 0))),
+			Box::new(TimeFrame::new(hm(13, 0), hm(14, 0))),
+		]);
+
+		assert_debug_snapshot_matches!(
+			"morning_or_noon",
+			union
+				.iter_within(Interval::from(
+					"2019-01-01T00:00:00Z".parse().unwrap()
+				))
+				.take(2)
+				.collect::<Vec<_>>()
+		);
+	}
+}
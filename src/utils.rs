@@ -1,54 +1,65 @@
-use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime};
 
+use crate::zone::Zone;
+
+/// A calendar period that contains other periods
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Of {
 	Day,
 	Month,
+	Quarter,
 	Year,
 }
 
-pub fn end_of(of: Of, date: &DateTime<Utc>) -> DateTime<Utc> {
-	match of {
-		Of::Year => end_of(Of::Month, &date.with_month(12).unwrap()),
-		Of::Month => end_of(
-			Of::Day,
-			&date
-				.with_day(days_in_month(date.year(), date.month()))
-				.unwrap(),
-		),
-		Of::Day => date
-			.with_hour(23)
-			.unwrap()
-			.with_minute(59)
-			.unwrap()
-			.with_second(59)
-			.unwrap()
-			.with_nanosecond(999999999)
-			.unwrap(),
-	}
+/// The start of the calendar period containing the date
+pub fn start_of(of: Of, date: &DateTime<Zone>) -> DateTime<Zone> {
+	let day = date.date_naive();
+	let day = match of {
+		Of::Year => NaiveDate::from_ymd_opt(day.year(), 1, 1).unwrap(),
+		Of::Quarter => NaiveDate::from_ymd_opt(
+			day.year(),
+			(day.month() - 1) / 3 * 3 + 1,
+			1,
+		)
+		.unwrap(),
+		Of::Month => day.with_day(1).unwrap(),
+		Of::Day => day,
+	};
+
+	date.timezone().localize(&day.and_time(NaiveTime::MIN))
 }
 
-pub fn start_of(of: Of, date: &DateTime<Utc>) -> DateTime<Utc> {
-	match of {
-		Of::Year => start_of(Of::Month, &date.with_month(1).unwrap()),
-		Of::Month => start_of(Of::Day, &date.with_day(1).unwrap()),
-		Of::Day => date
-			.with_hour(0)
-			.unwrap()
-			.with_minute(0)
-			.unwrap()
-			.with_second(0)
-			.unwrap()
-			.with_nanosecond(0)
-			.unwrap(),
-	}
+/// The exclusive end of the period, which is the start of the next one
+pub fn end_of(of: Of, date: &DateTime<Zone>) -> DateTime<Zone> {
+	let day = date.date_naive();
+	let day = match of {
+		Of::Year => NaiveDate::from_ymd_opt(day.year() + 1, 1, 1).unwrap(),
+		Of::Quarter => {
+			let start = start_of(Of::Quarter, date).date_naive();
+			let last = start.month() + 2;
+			NaiveDate::from_ymd_opt(
+				start.year(),
+				last,
+				days_in_month(start.year(), last),
+			)
+			.unwrap() + Duration::days(1)
+		}
+		Of::Month => {
+			day.with_day(days_in_month(day.year(), day.month()))
+				.unwrap() + Duration::days(1)
+		}
+		Of::Day => day + Duration::days(1),
+	};
+
+	date.timezone().localize(&day.and_time(NaiveTime::MIN))
 }
 
 pub fn days_in_month(year: i32, m: u32) -> u32 {
 	if m == 12 {
-		NaiveDate::from_ymd(year + 1, 1, 1)
+		NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap()
 	} else {
-		NaiveDate::from_ymd(year, m + 1, 1)
+		NaiveDate::from_ymd_opt(year, m + 1, 1).unwrap()
 	}
-	.signed_duration_since(NaiveDate::from_ymd(year, m, 1))
+	.signed_duration_since(NaiveDate::from_ymd_opt(year, m, 1).unwrap())
 	.num_days() as u32
 }
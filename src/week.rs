@@ -0,0 +1,121 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+
+use crate::interval::Interval;
+
+/// Whether a week number is even or odd
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Parity {
+	Even,
+	Odd,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Rule {
+	Number(u32),
+	Parity(Parity),
+}
+
+/// An iterator over weeks selected by their ISO 8601 week number.
+///
+/// Weeks that start on another day than monday take the number of the ISO
+/// week their fourth day falls into, so with the default start every week
+/// is numbered exactly as in ISO 8601. Years have either 52 or 53 weeks,
+/// which makes week 53 and week 1 of the next year both odd.
+pub struct WeekIterator {
+	rule: Rule,
+	start: Weekday,
+	interval: Interval,
+	completed: bool,
+}
+
+impl WeekIterator {
+	/// The week with the given number in every year
+	pub fn number(week: u32, start: Weekday, interval: Interval) -> Self {
+		WeekIterator {
+			completed: week == 0 || week > 53,
+			rule: Rule::Number(week),
+			start,
+			interval,
+		}
+	}
+
+	/// Every even or odd week
+	pub fn parity(parity: Parity, start: Weekday, interval: Interval) -> Self {
+		WeekIterator {
+			completed: false,
+			rule: Rule::Parity(parity),
+			start,
+			interval,
+		}
+	}
+
+	fn matches(&self, week_start: NaiveDate) -> bool {
+		let week = (week_start + Duration::days(3)).iso_week().week();
+		match self.rule {
+			Rule::Number(number) => week == number,
+			Rule::Parity(Parity::Even) => week.is_multiple_of(2),
+			Rule::Parity(Parity::Odd) => !week.is_multiple_of(2),
+		}
+	}
+}
+
+impl Iterator for WeekIterator {
+	type Item = Interval;
+
+	fn next(&mut self) -> Option<Interval> {
+		if self.completed {
+			return None;
+		}
+
+		let from = self.interval.from;
+		let zone = from.timezone();
+		let date = from.date_naive();
+
+		let offset = (7 + date.weekday().num_days_from_monday()
+			- self.start.num_days_from_monday())
+			% 7;
+		let mut week = date - Duration::days(i64::from(offset));
+
+		let start = loop {
+			if self.matches(week) {
+				let midnight = week.and_time(NaiveTime::MIN);
+				if let Some(start) = zone.period_start(&midnight) {
+					break start;
+				}
+				if self
+					.interval
+					.to
+					.is_some_and(|to| zone.localize(&midnight) >= to)
+				{
+					self.completed = true;
+					return None;
+				}
+			}
+			week += Duration::days(7);
+		};
+
+		let start = start.max(from);
+		let end =
+			zone.localize(&(week + Duration::days(7)).and_time(NaiveTime::MIN));
+
+		if self.interval.to.is_some() && start >= self.interval.to.unwrap() {
+			self.completed = true;
+			return None;
+		}
+
+		if self.interval.to.is_some() && self.interval.to.unwrap() < end {
+			self.completed = true;
+			return Some(Interval {
+				from: start,
+				to: self.interval.to,
+			});
+		}
+
+		self.interval.from = end;
+
+		Some(Interval {
+			from: start,
+			to: Some(end),
+		})
+	}
+}
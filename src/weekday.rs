@@ -1,7 +1,80 @@
-use chrono::{Datelike, Duration, Weekday};
+use chrono::{Datelike, Duration, NaiveTime, Weekday};
 
+use crate::combine::Combine;
 use crate::interval::{Interval, Timeline};
-use crate::utils::{end_of, start_of, Of};
+use crate::merge::Merge;
+use crate::types::Dimension;
+use crate::utils::{end_of, Of};
+
+/// Days of the week that are considered a weekend
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weekend(Vec<Weekday>);
+
+impl Weekend {
+	pub fn new(days: Vec<Weekday>) -> Self {
+		Weekend(days)
+	}
+
+	/// Friday and Saturday, as observed in most of the Middle East
+	pub fn fri_sat() -> Self {
+		Weekend(vec![Weekday::Fri, Weekday::Sat])
+	}
+
+	pub fn contains(&self, day: Weekday) -> bool {
+		self.0.contains(&day)
+	}
+
+	/// How many distinct days of the week belong to the weekend
+	pub(crate) fn days_per_week(&self) -> usize {
+		let mut days: Vec<u32> = self
+			.0
+			.iter()
+			.map(|day| day.num_days_from_monday())
+			.collect();
+		days.sort_unstable();
+		days.dedup();
+		days.len()
+	}
+
+	/// Iterate over weekends, consequent days are merged together
+	pub fn weekends(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		Box::new(Merge::new(
+			Combine::new(
+				self.0
+					.iter()
+					.map(|day| day.iter_within(interval.clone()))
+					.collect(),
+			),
+			self.0.len(),
+		))
+	}
+
+	/// Iterate over days that don't belong to a weekend
+	pub fn weekdays(
+		&self,
+		interval: Interval,
+	) -> Box<dyn Iterator<Item = Interval>> {
+		let mut day = Weekday::Mon;
+		let mut iterators: Vec<Box<dyn Iterator<Item = Interval>>> = vec![];
+		for _ in 0..7 {
+			if !self.contains(day) {
+				iterators.push(day.iter_within(interval.clone()));
+			}
+			day = day.succ();
+		}
+
+		Box::new(Combine::new(iterators))
+	}
+}
+
+impl Default for Weekend {
+	fn default() -> Self {
+		Weekend(vec![Weekday::Sat, Weekday::Sun])
+	}
+}
 
 impl Timeline for Weekday {
 	fn duration_hint(&self) -> Duration {
@@ -13,11 +86,15 @@ impl Timeline for Weekday {
 		interval: Interval,
 	) -> Box<dyn Iterator<Item = Interval>> {
 		Box::new(WeekdayIterator {
-			weekday: self.clone(),
+			weekday: *self,
 			interval,
 			completed: false,
 		})
 	}
+
+	fn cycle(&self) -> Option<Dimension> {
+		Some(Dimension::Week)
+	}
 }
 
 struct WeekdayIterator {
@@ -34,19 +111,34 @@ impl Iterator for WeekdayIterator {
 			return None;
 		}
 
-		let mut from = self.interval.from.clone();
-		let current_day = from.weekday();
+		let mut from = self.interval.from;
+		loop {
+			if self.interval.to.is_some() && from >= self.interval.to.unwrap() {
+				self.completed = true;
+				return None;
+			}
+
+			if from.weekday() == self.weekday {
+				break;
+			}
 
-		if self.weekday != current_day {
 			let target_num = self.weekday.num_days_from_monday();
-			let current_num = current_day.num_days_from_monday();
+			let current_num = from.weekday().num_days_from_monday();
 			let diff = target_num as i32 - current_num as i32;
 			let days_to_add = if diff > 0 {
 				diff as i64
 			} else {
 				7 + diff as i64
 			};
-			from = from + Duration::days(days_to_add)
+			// Calendar days are counted on the wall clock, an absolute
+			// number of hours would be off around DST transitions
+			let day = (from.date_naive() + Duration::days(days_to_add))
+				.and_time(NaiveTime::MIN);
+			match from.timezone().period_start(&day) {
+				Some(start) => from = start,
+				// The day is skipped, so we look for it a week later
+				None => from = end_of(Of::Day, &from.timezone().localize(&day)),
+			}
 		}
 
 		let end_of_day = end_of(Of::Day, &from);
@@ -55,11 +147,10 @@ impl Iterator for WeekdayIterator {
 			self.completed = true;
 			Some(Interval {
 				from,
-				to: self.interval.to.clone(),
+				to: self.interval.to,
 			})
 		} else {
-			self.interval.from =
-				start_of(Of::Day, &(end_of_day + Duration::seconds(10)));
+			self.interval.from = end_of_day;
 
 			Some(Interval {
 				from,
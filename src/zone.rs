@@ -0,0 +1,372 @@
+use std::fmt;
+
+use chrono::{
+	DateTime, Duration, FixedOffset, MappedLocalTime, NaiveDate, NaiveDateTime,
+	Offset, TimeZone,
+};
+use chrono_tz::{Tz, TzOffset};
+
+use crate::interval::Interval;
+
+/// What to do with wall clock times skipped when clocks go forward
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gap {
+	/// Don't produce an occurrence at all
+	Skip,
+	/// Move the occurrence forward by the length of the gap
+	ShiftForward,
+}
+
+/// What to do with wall clock times repeated when clocks go back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Overlap {
+	/// Use the first of the two instants
+	Earliest,
+	/// Use the second of the two instants
+	Latest,
+	/// Produce an occurrence at both instants
+	Both,
+}
+
+/// A policy to resolve wall clock times around daylight saving transitions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dst {
+	pub gap: Gap,
+	pub overlap: Overlap,
+}
+
+impl Default for Dst {
+	fn default() -> Self {
+		Dst {
+			gap: Gap::ShiftForward,
+			overlap: Overlap::Earliest,
+		}
+	}
+}
+
+/// An IANA time zone together with the daylight saving policy used to
+/// resolve wall clock times in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Zone {
+	tz: Tz,
+	dst: Dst,
+}
+
+impl Zone {
+	pub fn new(tz: Tz, dst: Dst) -> Self {
+		Zone { tz, dst }
+	}
+
+	pub fn tz(&self) -> Tz {
+		self.tz
+	}
+
+	pub fn dst(&self) -> Dst {
+		self.dst
+	}
+
+	/// Resolve a wall clock time to a single instant. Boundaries can't be
+	/// skipped or doubled, so times in a gap are always shifted forward and
+	/// repeated times resolve to the earliest instant unless
+	/// `Overlap::Latest` is requested.
+	pub fn localize(&self, local: &NaiveDateTime) -> DateTime<Zone> {
+		match self.from_local_datetime(local) {
+			MappedLocalTime::Single(datetime) => datetime,
+			MappedLocalTime::Ambiguous(earliest, latest) => {
+				match self.dst.overlap {
+					Overlap::Latest => latest,
+					Overlap::Earliest | Overlap::Both => earliest,
+				}
+			}
+			MappedLocalTime::None => self.shift_forward(local),
+		}
+	}
+
+	/// Resolve a wall clock time to the instants at which an occurrence
+	/// should happen according to the policy, in chronological order.
+	pub fn occurrences(&self, local: &NaiveDateTime) -> Vec<DateTime<Zone>> {
+		match self.from_local_datetime(local) {
+			MappedLocalTime::Single(datetime) => vec![datetime],
+			MappedLocalTime::Ambiguous(earliest, latest) => {
+				match self.dst.overlap {
+					Overlap::Earliest => vec![earliest],
+					Overlap::Latest => vec![latest],
+					Overlap::Both => vec![earliest, latest],
+				}
+			}
+			MappedLocalTime::None => match self.dst.gap {
+				Gap::Skip => vec![],
+				Gap::ShiftForward => vec![self.shift_forward(local)],
+			},
+		}
+	}
+
+	/// Resolve the wall clock time at which a calendar period like a day or
+	/// a month starts. A period can't start twice, so repeated times resolve
+	/// like [`localize`](Zone::localize), but a period starting at a skipped
+	/// time doesn't happen at all when the policy skips gaps.
+	pub fn period_start(
+		&self,
+		local: &NaiveDateTime,
+	) -> Option<DateTime<Zone>> {
+		match self.from_local_datetime(local) {
+			MappedLocalTime::None if self.dst.gap == Gap::Skip => None,
+			_ => Some(self.localize(local)),
+		}
+	}
+
+	/// Resolve a window between two wall clock times to the intervals it
+	/// covers. The window starts at each of the
+	/// [`occurrences`](Zone::occurrences) of its start and always ends at
+	/// the same instant, windows that would end before they start are
+	/// dropped.
+	pub fn windows(
+		&self,
+		from: &NaiveDateTime,
+		to: &NaiveDateTime,
+	) -> Vec<Interval> {
+		let to = self.localize(to);
+		self.occurrences(from)
+			.into_iter()
+			.filter(|from| *from < to)
+			.map(|from| Interval { from, to: Some(to) })
+			.collect()
+	}
+
+	fn shift_forward(&self, local: &NaiveDateTime) -> DateTime<Zone> {
+		// Reading the time with the offset in effect a day earlier moves it
+		// forward by exactly the length of the gap
+		let before = self
+			.tz
+			.offset_from_utc_datetime(&(*local - Duration::days(1)));
+		self.from_utc_datetime(&(*local - before.fix()))
+	}
+
+	fn wrap(&self, offset: TzOffset) -> ZoneOffset {
+		ZoneOffset {
+			offset,
+			dst: self.dst,
+		}
+	}
+}
+
+impl From<Tz> for Zone {
+	fn from(tz: Tz) -> Self {
+		Zone::new(tz, Dst::default())
+	}
+}
+
+/// The offset of a [`Zone`] at a particular instant
+#[derive(Clone, Copy, PartialEq)]
+pub struct ZoneOffset {
+	offset: TzOffset,
+	dst: Dst,
+}
+
+impl Offset for ZoneOffset {
+	fn fix(&self) -> FixedOffset {
+		self.offset.fix()
+	}
+}
+
+impl fmt::Debug for ZoneOffset {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Debug::fmt(&self.offset, f)
+	}
+}
+
+impl fmt::Display for ZoneOffset {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(&self.offset, f)
+	}
+}
+
+impl TimeZone for Zone {
+	type Offset = ZoneOffset;
+
+	fn from_offset(offset: &ZoneOffset) -> Self {
+		Zone {
+			tz: Tz::from_offset(&offset.offset),
+			dst: offset.dst,
+		}
+	}
+
+	fn offset_from_local_date(
+		&self,
+		local: &NaiveDate,
+	) -> MappedLocalTime<ZoneOffset> {
+		self.tz
+			.offset_from_local_date(local)
+			.map(|offset| self.wrap(offset))
+	}
+
+	fn offset_from_local_datetime(
+		&self,
+		local: &NaiveDateTime,
+	) -> MappedLocalTime<ZoneOffset> {
+		self.tz
+			.offset_from_local_datetime(local)
+			.map(|offset| self.wrap(offset))
+	}
+
+	fn offset_from_utc_date(&self, utc: &NaiveDate) -> ZoneOffset {
+		self.wrap(self.tz.offset_from_utc_date(utc))
+	}
+
+	fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> ZoneOffset {
+		self.wrap(self.tz.offset_from_utc_datetime(utc))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::duration::Anchor;
+	use crate::interval::{Interval, Timeline};
+	use crate::period::Period;
+	use crate::timeframe::TimeFrame;
+	use chrono::{NaiveTime, Utc, Weekday};
+	use chrono_tz::{America, Europe};
+
+	fn at_2_30(dst: Dst, from: &str, to: &str) -> Vec<DateTime<Utc>> {
+		let interval = Interval::from(from.parse().unwrap())
+			.until(to.parse().unwrap())
+			.with_timezone(Zone::new(Europe::Berlin, dst));
+
+		NaiveTime::from_hms_opt(2, 30, 0)
+			.unwrap()
+			.iter_within(interval)
+			.map(|interval| interval.from.with_timezone(&Utc))
+			.collect()
+	}
+
+	fn starts(timeline: &dyn Timeline, dst: Dst, from: &str) -> Vec<String> {
+		let interval = Interval::from(from.parse().unwrap())
+			.with_timezone(Zone::new(Europe::Berlin, dst));
+
+		timeline
+			.iter_within(interval)
+			.take(3)
+			.map(|interval| {
+				interval
+					.from
+					.with_timezone(&Utc)
+					.format("%m-%d %H:%M")
+					.to_string()
+			})
+			.collect()
+	}
+
+	fn half_past_two_to_four() -> TimeFrame {
+		TimeFrame::new(
+			NaiveTime::from_hms_opt(2, 30, 0).unwrap(),
+			NaiveTime::from_hms_opt(4, 0, 0).unwrap(),
+		)
+	}
+
+	fn daily_at_2_30() -> Period {
+		Period::Fixed(
+			Duration::days(1),
+			Anchor::Epoch(
+				NaiveDate::from_ymd_opt(2019, 1, 1)
+					.unwrap()
+					.and_hms_opt(2, 30, 0)
+					.unwrap(),
+			),
+		)
+	}
+
+	fn utc(datetimes: &[&str]) -> Vec<DateTime<Utc>> {
+		datetimes.iter().map(|d| d.parse().unwrap()).collect()
+	}
+
+	#[test]
+	fn spring_forward() {
+		let (from, to) = ("2019-03-30T00:00:00Z", "2019-04-01T12:00:00Z");
+
+		assert_eq!(
+			at_2_30(Dst::default(), from, to),
+			utc(&[
+				"2019-03-30T01:30:00Z",
+				"2019-03-31T01:30:00Z",
+				"2019-04-01T00:30:00Z",
+			])
+		);
+
+		let skip = Dst {
+			gap: Gap::Skip,
+			..Dst::default()
+		};
+		assert_eq!(
+			at_2_30(skip, from, to),
+			utc(&["2019-03-30T01:30:00Z", "2019-04-01T00:30:00Z"])
+		);
+	}
+
+	#[test]
+	fn fall_back() {
+		let (from, to) = ("2019-10-27T00:00:00Z", "2019-10-27T12:00:00Z");
+		let overlap = |overlap| Dst {
+			overlap,
+			..Dst::default()
+		};
+
+		assert_eq!(
+			at_2_30(overlap(Overlap::Earliest), from, to),
+			utc(&["2019-10-27T00:30:00Z"])
+		);
+		assert_eq!(
+			at_2_30(overlap(Overlap::Latest), from, to),
+			utc(&["2019-10-27T01:30:00Z"])
+		);
+		assert_eq!(
+			at_2_30(overlap(Overlap::Both), from, to),
+			utc(&["2019-10-27T00:30:00Z", "2019-10-27T01:30:00Z"])
+		);
+	}
+
+	#[test]
+	fn windows_across_transitions() {
+		let skip = Dst {
+			gap: Gap::Skip,
+			..Dst::default()
+		};
+		let both = Dst {
+			overlap: Overlap::Both,
+			..Dst::default()
+		};
+		for timeline in &[
+			Box::new(half_past_two_to_four()) as Box<dyn Timeline>,
+			Box::new(daily_at_2_30()),
+		] {
+			let spring = "2019-03-30T00:00:00Z";
+			assert_eq!(
+				starts(timeline, Dst::default(), spring),
+				vec!["03-30 01:30", "03-31 01:30", "04-01 00:30"]
+			);
+			assert_eq!(
+				starts(timeline, skip, spring),
+				vec!["03-30 01:30", "04-01 00:30", "04-02 00:30"]
+			);
+
+			let fall = "2019-10-27T00:00:00Z";
+			assert_eq!(
+				starts(timeline, both, fall),
+				vec!["10-27 00:30", "10-27 01:30", "10-28 01:30"]
+			);
+		}
+	}
+
+	#[test]
+	fn missing_midnight() {
+		// Clocks in Sao Paulo went forward at midnight on 2018-11-04
+		let interval = Interval::from("2018-11-01T12:00:00Z".parse().unwrap())
+			.with_timezone(America::Sao_Paulo);
+
+		let sunday = Weekday::Sun.iter_within(interval).next().unwrap();
+		assert_eq!(
+			sunday.from.with_timezone(&Utc),
+			"2018-11-04T03:00:00Z".parse::<DateTime<Utc>>().unwrap()
+		);
+	}
+}